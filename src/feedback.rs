@@ -0,0 +1,37 @@
+//! Feedback on a submitted input, for users who can hardly see the cursor move.
+//!
+
+use std::io;
+use std::thread::sleep;
+use std::time::Duration;
+
+use console::Term;
+
+/// Duration of the screen flash.
+const FLASH_DURATION: Duration = Duration::from_millis(100);
+
+/// Kind of the feedback emitted when an input is submitted or a selection is confirmed.
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Feedback {
+    /// ring the terminal bell
+    Bell,
+    /// flash the screen in the reverse video briefly
+    Flash,
+}
+
+impl Feedback {
+    /// Emit the feedback on the terminal.
+    ///
+    pub(crate) fn emit(self, term: &Term) -> io::Result<()> {
+        match self {
+            Feedback::Bell => term.write_str("\x07"),
+            Feedback::Flash => {
+                term.write_str("\x1b[?5h")?;
+                term.flush()?;
+                sleep(FLASH_DURATION);
+                term.write_str("\x1b[?5l")
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! A form which chains prompts, with back navigation.
+//!
+//! Each field is prompted in order, and Shift-Tab goes back to the previous field,
+//! where the value entered before is kept as the initial value. Left goes back as well
+//! when there is nothing to move left to in the field.
+//!
+//! ```rust,no_run
+//! use ttyui::form::{Field, Form};
+//!
+//! let values = Form::new()
+//!     .field(Field::Text("name".to_string()))
+//!     .field(Field::Date("birthday".to_string()))
+//!     .field(Field::Pick(
+//!         "animal".to_string(),
+//!         vec!["Elephant".to_string(), "Horse".to_string()],
+//!     ))
+//!     .field(Field::YesNo("subscribe".to_string()))
+//!     .run()
+//!     .unwrap();
+//! println!("{:?}", values);
+//! ```
+//!
+
+use std::io;
+use std::io::Write;
+
+use crate::key::read_plain_key_or_resize;
+use crate::readline::{Buffer, KeyAction};
+use crate::selector::{DateSelector, DateTimeField, ListOptions, Selector, WordSelector};
+use chrono::{DateTime, Local};
+use console::{Key, Term};
+
+/// Field of a form, with its label.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// text input with readline
+    Text(String),
+    /// date input with DateSelector
+    Date(String),
+    /// y/n answer
+    YesNo(String),
+    /// item selection from the items
+    Pick(String, Vec<String>),
+}
+
+/// Value entered for a field.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldValue {
+    /// text entered for Field::Text
+    Text(String),
+    /// date selected for Field::Date
+    Date(DateTime<Local>),
+    /// answer for Field::YesNo, true for y
+    YesNo(bool),
+    /// item selected for Field::Pick
+    Pick(String),
+}
+
+/// Sequence of fields to be prompted in order.
+///
+#[derive(Clone, Debug)]
+pub struct Form {
+    fields: Vec<Field>,
+    term: Term,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    /// Generate a form without fields.
+    ///
+    pub fn new() -> Self {
+        Form {
+            fields: Vec::new(),
+            term: Term::stdout(),
+        }
+    }
+
+    /// Generate a form without fields, whose prompts share the terminal.
+    ///
+    pub fn with_term(term: Term) -> Self {
+        Form {
+            fields: Vec::new(),
+            term,
+        }
+    }
+
+    /// Add a field at the tail of the form.
+    ///
+    pub fn field(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Prompt the fields in order and return the values for them.
+    ///
+    /// Shift-Tab goes back to the previous field, where the entered value is kept.
+    /// Left goes back as well at the head of the text, on the year of the date,
+    /// on the y/n question and on the items not scrolled.
+    /// Quitting the item selection results io::Error::Other, as select_word_from_words() does.
+    ///
+    pub fn run(&self) -> io::Result<Vec<FieldValue>> {
+        let values = self.run_with(|field, prev| {
+            self.term.clear_screen()?;
+            self.run_field(field, prev)
+        })?;
+        self.term.clear_screen()?;
+        Ok(values)
+    }
+
+    /// Prompt the fields in order with `prompt`, which returns None to go back.
+    ///
+    fn run_with<F>(&self, mut prompt: F) -> io::Result<Vec<FieldValue>>
+    where
+        F: FnMut(&Field, Option<&FieldValue>) -> io::Result<Option<FieldValue>>,
+    {
+        let mut values: Vec<Option<FieldValue>> = vec![None; self.fields.len()];
+        let mut i = 0;
+        while i < self.fields.len() {
+            match prompt(&self.fields[i], values[i].as_ref())? {
+                Some(v) => {
+                    values[i] = Some(v);
+                    i += 1;
+                }
+                None => i = i.saturating_sub(1),
+            }
+        }
+        Ok(values.into_iter().flatten().collect())
+    }
+
+    /// Prompt a field with the value entered before, and return None to go back.
+    ///
+    fn run_field(
+        &self,
+        field: &Field,
+        prev: Option<&FieldValue>,
+    ) -> io::Result<Option<FieldValue>> {
+        match field {
+            Field::Text(label) => {
+                let mut buf = match prev {
+                    Some(FieldValue::Text(text)) => Buffer::from(text),
+                    _ => Buffer::new(),
+                };
+                buf.set_term(self.term.clone());
+                buf.set_prefix(format!("{}: ", label));
+                buf.set_on_key(
+                    |key, buf| match is_back_key(&key, buf.snapshot().index() == 0) {
+                        true => KeyAction::Terminate,
+                        false => KeyAction::Passthrough,
+                    },
+                );
+                match buf.read_line()? {
+                    key if is_back_key(&key, true) => Ok(None),
+                    _ => Ok(Some(FieldValue::Text(buf.to_string()))),
+                }
+            }
+            Field::Date(label) => {
+                let mut d = DateSelector::with_term(self.term.clone());
+                if let Some(FieldValue::Date(date)) = prev {
+                    d.set_date(*date);
+                }
+                d.name = label.clone();
+                d.normalize();
+                let date =
+                    self.prompt(&mut d, |d| d.active_field_value().0 == DateTimeField::Year)?;
+                Ok(date.map(FieldValue::Date))
+            }
+            Field::YesNo(label) => {
+                let prev = match prev {
+                    Some(FieldValue::YesNo(answer)) => Some(*answer),
+                    _ => None,
+                };
+                self.ask_yes_no(label, prev)
+            }
+            Field::Pick(label, items) => {
+                let mut s = WordSelector::with_term(
+                    label,
+                    items,
+                    ListOptions::default(),
+                    self.term.clone(),
+                )?;
+                if let Some(FieldValue::Pick(item)) = prev {
+                    s.preselect(item);
+                }
+                let item = self.prompt(&mut s, |s| !s.is_scrolled())?;
+                Ok(item.map(FieldValue::Pick))
+            }
+        }
+    }
+
+    /// Run a selector until it results a value, or return None for a key to go back.
+    ///
+    /// `at_head` tells whether Left has nothing to move to in the selector.
+    ///
+    fn prompt<S: Selector>(
+        &self,
+        s: &mut S,
+        at_head: impl Fn(&S) -> bool,
+    ) -> io::Result<Option<S::Output>> {
+        loop {
+            s.render()?;
+            match read_plain_key_or_resize(&self.term, s.cancel_flag())? {
+                Some(key) if is_back_key(&key, at_head(s)) => return Ok(None),
+                Some(key) => {
+                    if let Some(value) = s.handle_key(key)? {
+                        return Ok(Some(value));
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Ask y/n for a field, where Enter keeps the answer given before.
+    ///
+    fn ask_yes_no(&self, label: &str, prev: Option<bool>) -> io::Result<Option<FieldValue>> {
+        match prev {
+            Some(answer) => write!(&self.term, "{} [{}]: ", label, yes_no_char(answer))?,
+            None => write!(&self.term, "{} (y/n): ", label)?,
+        }
+        loop {
+            let answer = match self.term.read_key()? {
+                Key::Char('Y') | Key::Char('y') => true,
+                Key::Char('N') | Key::Char('n') => false,
+                Key::Enter => match prev {
+                    Some(answer) => answer,
+                    None => continue,
+                },
+                key if is_back_key(&key, true) => return Ok(None),
+                _ => continue,
+            };
+            writeln!(&self.term, "{}", yes_no_char(answer))?;
+            return Ok(Some(FieldValue::YesNo(answer)));
+        }
+    }
+}
+
+/// Whether the key goes back to the previous field, where Left does only at the head of the field.
+///
+fn is_back_key(key: &Key, at_head: bool) -> bool {
+    match key {
+        Key::BackTab => true,
+        Key::ArrowLeft => at_head,
+        _ => false,
+    }
+}
+
+fn yes_no_char(answer: bool) -> char {
+    if answer {
+        'y'
+    } else {
+        'n'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::form::*;
+
+    fn form() -> Form {
+        Form::new()
+            .field(Field::Text("name".to_string()))
+            .field(Field::YesNo("subscribe".to_string()))
+            .field(Field::Pick("animal".to_string(), vec!["Horse".to_string()]))
+    }
+
+    #[test]
+    fn field_appends_fields_in_order() {
+        let f = Form::new()
+            .field(Field::Text("name".to_string()))
+            .field(Field::YesNo("subscribe".to_string()));
+        assert_eq!(
+            f.fields,
+            vec![
+                Field::Text("name".to_string()),
+                Field::YesNo("subscribe".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn run_collects_values_in_order() {
+        let mut answers = vec![
+            FieldValue::Text("kabuki".to_string()),
+            FieldValue::YesNo(true),
+            FieldValue::Pick("Horse".to_string()),
+        ]
+        .into_iter();
+        let values = form().run_with(|_, _| Ok(answers.next())).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                FieldValue::Text("kabuki".to_string()),
+                FieldValue::YesNo(true),
+                FieldValue::Pick("Horse".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn run_goes_back_with_the_entered_value() {
+        let mut prompted = Vec::new();
+        let mut answers = vec![
+            Some(FieldValue::Text("kabuki".to_string())),
+            Some(FieldValue::YesNo(true)),
+            None,
+            None,
+            Some(FieldValue::Text("noh".to_string())),
+            Some(FieldValue::YesNo(false)),
+            Some(FieldValue::Pick("Horse".to_string())),
+        ]
+        .into_iter();
+        let values = form()
+            .run_with(|field, prev| {
+                prompted.push((field.clone(), prev.cloned()));
+                Ok(answers.next().unwrap())
+            })
+            .unwrap();
+        // back from the pick to the y/n, and from the y/n to the text
+        assert_eq!(prompted[2].0, form().fields[2]);
+        assert_eq!(prompted[3].1, Some(FieldValue::YesNo(true)));
+        assert_eq!(prompted[4].1, Some(FieldValue::Text("kabuki".to_string())));
+        assert_eq!(prompted[5].1, Some(FieldValue::YesNo(true)));
+        assert_eq!(
+            values,
+            vec![
+                FieldValue::Text("noh".to_string()),
+                FieldValue::YesNo(false),
+                FieldValue::Pick("Horse".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn run_stays_on_the_first_field_going_back() {
+        let mut answers = vec![None, Some(FieldValue::YesNo(true))].into_iter();
+        let f = Form::new().field(Field::YesNo("subscribe".to_string()));
+        let values = f.run_with(|_, _| Ok(answers.next().unwrap())).unwrap();
+        assert_eq!(values, vec![FieldValue::YesNo(true)]);
+    }
+
+    #[test]
+    fn back_keys_are_shift_tab_and_left_at_the_head() {
+        assert!(is_back_key(&Key::BackTab, false));
+        assert!(is_back_key(&Key::ArrowLeft, true));
+        assert!(!is_back_key(&Key::ArrowLeft, false));
+        assert!(!is_back_key(&Key::Tab, true));
+    }
+}
@@ -0,0 +1,238 @@
+//! Key reading with the Alt (Meta) modifier collapsed into a single value.
+//!
+//! Terminals send Alt+key as the key prefixed with Escape, such as `ESC f` for Alt+f
+//! or `ESC [ 1 ; 3 D` for Alt+Left, which console::Term reads as an unknown escape sequence
+//! and may leave the tail of it unread.
+//!
+
+use std::io;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use crate::poll::{read_key_or_resize, read_key_timeout};
+use console::{Key, Term};
+
+/// Time to wait for the rest of an escape sequence, which a terminal sends at once.
+const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Key read from the terminal, with or without the Alt modifier.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NormalizedKey {
+    /// key without the Alt modifier, or an escape sequence which is not known as Alt+key
+    Key(Key),
+    /// key pressed with the Alt modifier, such as `Alt(Key::Char('f'))` or `Alt(Key::ArrowLeft)`
+    Alt(Key),
+}
+
+/// Read a single key from the standard output terminal, without echo.
+///
+/// ```rust,no_run
+/// use console::Key;
+/// use ttyui::key::read_key;
+///
+/// if read_key().unwrap() == Key::Char('q') {
+///     println!("quit");
+/// }
+/// ```
+///
+pub fn read_key() -> io::Result<Key> {
+    Term::stdout().read_key()
+}
+
+/// Show the prompt and wait for any key, which is not echoed.
+///
+/// ```rust,no_run
+/// use ttyui::key::pause;
+/// pause("Press any key to continue...").unwrap();
+/// ```
+///
+pub fn pause(prompt: &str) -> io::Result<()> {
+    let term = Term::stdout();
+    term.write_str(prompt)?;
+    term.read_key()?;
+    term.write_line("")
+}
+
+/// Read a key, where the escape-prefixed sequences are collapsed into NormalizedKey::Alt.
+///
+/// A lone Escape is returned as `NormalizedKey::Key(Key::Escape)`. The keys sent as `ESC O` sequences
+/// in the application mode of the terminal are returned as the plain keys, such as `Key::Char('1')`
+/// for 1 on the numeric keypad and `Key::ArrowUp` for the up arrow.
+///
+/// ```rust,no_run
+/// use console::{Key, Term};
+/// use ttyui::key::{read_key_normalized, NormalizedKey};
+///
+/// match read_key_normalized(&Term::stdout()).unwrap() {
+///     NormalizedKey::Alt(Key::Char(c)) => println!("Alt+{}", c),
+///     NormalizedKey::Alt(key) => println!("Alt+{:?}", key),
+///     NormalizedKey::Key(key) => println!("{:?}", key),
+/// }
+/// ```
+///
+pub fn read_key_normalized(term: &Term) -> io::Result<NormalizedKey> {
+    let key = term.read_key()?;
+    normalize(term, key)
+}
+
+/// Read a key for the selectors, which have no binding with Alt.
+///
+/// An Alt key is read to the end of its sequence and results None as a resize does,
+/// so that the tail of the sequence is not taken as typed characters.
+///
+pub(crate) fn read_plain_key_or_resize(
+    term: &Term,
+    cancel: Option<&AtomicBool>,
+) -> io::Result<Option<Key>> {
+    match read_key_or_resize(term, cancel)? {
+        Some(key) => match normalize(term, key)? {
+            NormalizedKey::Key(key) => Ok(Some(key)),
+            NormalizedKey::Alt(_) => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Collapse a key read by console::Term into NormalizedKey, reading the rest of the sequence if needed.
+///
+pub(crate) fn normalize(term: &Term, key: Key) -> io::Result<NormalizedKey> {
+    let seq = match key {
+        Key::UnknownEscSeq(seq) => seq,
+        key => return Ok(NormalizedKey::Key(key)),
+    };
+    Ok(match seq.as_slice() {
+        ['\x7f'] | ['\x08'] => NormalizedKey::Alt(Key::Backspace),
+        ['\r'] | ['\n'] => NormalizedKey::Alt(Key::Enter),
+        // SS3 sequences such as `ESC O p`, which is told from Alt+O by the character arriving at once
+        ['O'] => match read_key_timeout(term, Duration::ZERO)? {
+            Some(key) => match ss3_key(&key) {
+                Some(key) => NormalizedKey::Key(key),
+                None => NormalizedKey::Key(Key::UnknownEscSeq(vec!['O'])),
+            },
+            None => NormalizedKey::Alt(Key::Char('O')),
+        },
+        [c] if !c.is_control() && *c != '[' => NormalizedKey::Alt(Key::Char(*c)),
+        // modified keys such as `ESC [ 1 ; 3 D`, whose modifier and final character are left unread
+        ['[', '1', ';'] => {
+            let modifier = read_key_timeout(term, SEQUENCE_TIMEOUT)?;
+            let last = match modifier {
+                Some(_) => read_key_timeout(term, SEQUENCE_TIMEOUT)?,
+                None => None,
+            };
+            if let (Some(Key::Char('3')), Some(key)) =
+                (&modifier, last.as_ref().and_then(final_key))
+            {
+                return Ok(NormalizedKey::Alt(key));
+            }
+            let mut seq = seq.clone();
+            for key in [modifier, last].into_iter().flatten() {
+                if let Key::Char(c) = key {
+                    seq.push(c);
+                }
+            }
+            NormalizedKey::Key(Key::UnknownEscSeq(seq))
+        }
+        // escape-prefixed sequences such as `ESC ESC [ D`
+        ['\x1b'] => match read_key_timeout(term, SEQUENCE_TIMEOUT)? {
+            Some(Key::Char('[')) => match read_key_timeout(term, SEQUENCE_TIMEOUT)? {
+                Some(last) => match final_key(&last) {
+                    Some(key) => NormalizedKey::Alt(key),
+                    None => NormalizedKey::Key(Key::UnknownEscSeq(seq)),
+                },
+                None => NormalizedKey::Key(Key::UnknownEscSeq(seq)),
+            },
+            Some(Key::Char(c)) => NormalizedKey::Alt(Key::Char(c)),
+            _ => NormalizedKey::Key(Key::UnknownEscSeq(seq)),
+        },
+        _ => NormalizedKey::Key(Key::UnknownEscSeq(seq)),
+    })
+}
+
+/// Key for the final character of a cursor key sequence, such as `D` for Left.
+///
+fn final_key(key: &Key) -> Option<Key> {
+    match key {
+        Key::Char('A') => Some(Key::ArrowUp),
+        Key::Char('B') => Some(Key::ArrowDown),
+        Key::Char('C') => Some(Key::ArrowRight),
+        Key::Char('D') => Some(Key::ArrowLeft),
+        Key::Char('H') => Some(Key::Home),
+        Key::Char('F') => Some(Key::End),
+        _ => None,
+    }
+}
+
+/// Key for the final character of an SS3 sequence, sent for the cursor keys and the numeric keypad
+/// in the application mode.
+///
+fn ss3_key(key: &Key) -> Option<Key> {
+    let c = match key {
+        Key::Char(c) => *c,
+        _ => return None,
+    };
+    match c {
+        'M' => Some(Key::Enter),
+        'p'..='y' => Some(Key::Char((b'0' + (c as u8 - b'p')) as char)),
+        'j' => Some(Key::Char('*')),
+        'k' => Some(Key::Char('+')),
+        'l' => Some(Key::Char(',')),
+        'm' => Some(Key::Char('-')),
+        'n' => Some(Key::Char('.')),
+        'o' => Some(Key::Char('/')),
+        'X' => Some(Key::Char('=')),
+        _ => final_key(key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::key::*;
+
+    #[test]
+    fn plain_keys_are_kept() {
+        let term = Term::stdout();
+        for key in [Key::Char('f'), Key::Escape, Key::ArrowLeft] {
+            assert_eq!(
+                normalize(&term, key.clone()).unwrap(),
+                NormalizedKey::Key(key)
+            );
+        }
+    }
+
+    #[test]
+    fn keypad_sequences_are_plain_keys() {
+        assert_eq!(ss3_key(&Key::Char('p')), Some(Key::Char('0')));
+        assert_eq!(ss3_key(&Key::Char('y')), Some(Key::Char('9')));
+        assert_eq!(ss3_key(&Key::Char('M')), Some(Key::Enter));
+        assert_eq!(ss3_key(&Key::Char('A')), Some(Key::ArrowUp));
+        assert_eq!(ss3_key(&Key::Char('z')), None);
+    }
+
+    #[test]
+    fn escape_prefixed_chars_are_alt() {
+        let term = Term::stdout();
+        let alt = |seq: Vec<char>| normalize(&term, Key::UnknownEscSeq(seq)).unwrap();
+        assert_eq!(alt(vec!['f']), NormalizedKey::Alt(Key::Char('f')));
+        assert_eq!(alt(vec!['\x7f']), NormalizedKey::Alt(Key::Backspace));
+        assert_eq!(
+            alt(vec!['[', '2', '4']),
+            NormalizedKey::Key(Key::UnknownEscSeq(vec!['[', '2', '4']))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn truncated_sequences_do_not_block() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("truncated");
+        for seq in [vec!['\x1b'], vec!['[', '1', ';']] {
+            assert_eq!(
+                normalize(&term, Key::UnknownEscSeq(seq.clone())).unwrap(),
+                NormalizedKey::Key(Key::UnknownEscSeq(seq))
+            );
+        }
+        drop(term);
+        output(&path);
+    }
+}
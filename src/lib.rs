@@ -4,7 +4,14 @@
 //! * readline
 //! * y/n selector
 //! * date and time selector
+//! * form chaining the prompts above
+//! * terminal setup restored around the prompts
 //!
 
+pub mod feedback;
+pub mod form;
+pub mod key;
+mod poll;
 pub mod readline;
 pub mod selector;
+pub mod terminal;
@@ -0,0 +1,222 @@
+//! Key reading with timeout, which console::Term does not provide.
+//!
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use console::{Key, Term};
+
+/// Interval to check the terminal size while waiting for a key.
+#[cfg(unix)]
+const RESIZE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Read a key if it arrives within the timeout, or return None.
+///
+/// A terminal in the canonical mode does not expose keys typed ahead until a newline,
+/// so the tty is switched to the raw mode while it is polled.
+/// For a non-tty, it waits for the timeout and returns None.
+///
+#[cfg(unix)]
+pub(crate) fn read_key_timeout(term: &Term, timeout: Duration) -> io::Result<Option<Key>> {
+    if !term.is_term() {
+        std::thread::sleep(timeout);
+        return Ok(None);
+    }
+    with_raw_tty(|fd| match poll_in(fd, timeout)? {
+        // the key is read while the tty is still raw, so that no typed-ahead key is lost
+        true => term.read_key().map(Some),
+        false => Ok(None),
+    })
+}
+
+/// Read a key, or return None when the terminal is resized before any key arrives,
+/// for the caller to redraw the layout with the new size.
+///
+/// The tty is kept raw while the size is checked, not to echo keys typed between the checks.
+/// When the `cancel` flag is set while waiting, it results the error from `cancelled()`.
+///
+#[cfg(unix)]
+pub(crate) fn read_key_or_resize(
+    term: &Term,
+    cancel: Option<&AtomicBool>,
+) -> io::Result<Option<Key>> {
+    if is_cancelled(cancel) {
+        return Err(cancelled());
+    }
+    if !term.is_term() {
+        return term.read_key().map(Some);
+    }
+    let size = term.size();
+    with_raw_tty(|fd| loop {
+        if poll_in(fd, RESIZE_CHECK_INTERVAL)? {
+            return term.read_key().map(Some);
+        }
+        if is_cancelled(cancel) {
+            return Err(cancelled());
+        }
+        if term.size() != size {
+            return Ok(None);
+        }
+    })
+}
+
+/// Error for a prompt cancelled with the flag, which is io::ErrorKind::Interrupted.
+///
+pub(crate) fn cancelled() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, Cancelled)
+}
+
+/// Whether the error results from `cancelled()`, rather than an interrupt such as Ctrl-C.
+///
+pub(crate) fn is_cancelled_error(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<Cancelled>())
+}
+
+/// Payload of the error for a cancelled prompt.
+///
+#[derive(Debug)]
+struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+}
+
+/// Run `f` with the file descriptor of the tty switched to the raw mode, and restore the mode.
+///
+#[cfg(unix)]
+fn with_raw_tty<T>(f: impl FnOnce(libc::c_int) -> io::Result<T>) -> io::Result<T> {
+    let raw = RawTty::enter()?;
+    let res = f(raw.fd);
+    raw.restore()?;
+    res
+}
+
+/// Tty switched to the raw mode, which is restored on restore() or drop, even on a panic.
+///
+/// The output processing is kept, so that newlines are still written as CRLF.
+///
+#[cfg(unix)]
+pub(crate) struct RawTty {
+    fd: libc::c_int,
+    /// /dev/tty opened when stdin is not a tty, which is closed on drop
+    _tty: Option<std::fs::File>,
+    original: Option<libc::termios>,
+}
+
+#[cfg(unix)]
+impl RawTty {
+    /// Switch stdin, or /dev/tty if stdin is not a tty, to the raw mode.
+    ///
+    pub(crate) fn enter() -> io::Result<Self> {
+        use std::fs;
+        use std::os::unix::io::AsRawFd;
+
+        let (fd, tty) = if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+            (libc::STDIN_FILENO, None)
+        } else {
+            let f = fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open("/dev/tty")?;
+            (f.as_raw_fd(), Some(f))
+        };
+
+        let mut termios = std::mem::MaybeUninit::uninit();
+        c_result(unsafe { libc::tcgetattr(fd, termios.as_mut_ptr()) })?;
+        let mut termios = unsafe { termios.assume_init() };
+        let original = termios;
+        unsafe { libc::cfmakeraw(&mut termios) };
+        termios.c_oflag = original.c_oflag;
+        c_result(unsafe { libc::tcsetattr(fd, libc::TCSADRAIN, &termios) })?;
+        Ok(RawTty {
+            fd,
+            _tty: tty,
+            original: Some(original),
+        })
+    }
+
+    /// Restore the mode of the tty, with the error which drop cannot report.
+    ///
+    pub(crate) fn restore(mut self) -> io::Result<()> {
+        self.reset()
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        match self.original.take() {
+            Some(original) => {
+                c_result(unsafe { libc::tcsetattr(self.fd, libc::TCSADRAIN, &original) })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawTty {
+    fn drop(&mut self) {
+        let _ = self.reset();
+    }
+}
+
+/// Wait for the file descriptor to be readable within the timeout.
+///
+#[cfg(unix)]
+fn poll_in(fd: libc::c_int, timeout: Duration) -> io::Result<bool> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+    match unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } {
+        n if n < 0 => Err(io::Error::last_os_error()),
+        _ => Ok(pollfd.revents & libc::POLLIN != 0),
+    }
+}
+
+/// Read a key if it arrives within the timeout, or return None.
+///
+/// Polling is not supported on this platform, so it blocks until a key is pressed,
+/// except for the zero timeout which returns None at once.
+///
+#[cfg(not(unix))]
+pub(crate) fn read_key_timeout(term: &Term, timeout: Duration) -> io::Result<Option<Key>> {
+    if timeout.is_zero() {
+        return Ok(None);
+    }
+    term.read_key().map(Some)
+}
+
+/// Read a key, or return None when the terminal is resized before any key arrives.
+///
+/// Resizing is not detected on this platform, so it simply blocks until a key is pressed.
+/// The `cancel` flag is checked only before the key is read.
+///
+#[cfg(not(unix))]
+pub(crate) fn read_key_or_resize(
+    term: &Term,
+    cancel: Option<&AtomicBool>,
+) -> io::Result<Option<Key>> {
+    if is_cancelled(cancel) {
+        return Err(cancelled());
+    }
+    term.read_key().map(Some)
+}
+
+#[cfg(unix)]
+fn c_result(res: libc::c_int) -> io::Result<()> {
+    if res != 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This module realizes traditional line editor implementation with Emacs-like shortcuts.
 //!
-//! ```rust
+//! ```rust,no_run
 //! use std::env;
 //! use ttyui::readline::Buffer;
 //!
@@ -23,57 +23,325 @@
 //! ```
 //!
 
-use console::{Key, Term};
+use crate::feedback::Feedback;
+use crate::key::{normalize, NormalizedKey};
+use crate::poll::{cancelled, is_cancelled_error, read_key_or_resize, read_key_timeout};
+use console::{measure_text_width, strip_ansi_codes, truncate_str, Key, Term};
+use std::collections::HashMap;
 use std::io;
 use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
 
-const MAX_PREFIX_CAPACITY: usize = 32;
-const DEFAULT_TEXT_CAPACITY: usize = 1024;
+/// Separator of words for the word motions and the word count.
+const WORD_SEPARATOR: char = ' ';
+const DEFAULT_TAB_WIDTH: usize = 8;
+const EMPTY_INPUT_MESSAGE: &str = "(input required)";
+
+/// Callback to be notified with the text on each edit.
+type OnChange = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Callback to receive the log lines of the debug mode.
+type DebugSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Callback to intercept each key read, which is not copied to the clones of the buffer.
+type OnKey = Box<dyn FnMut(Key, &mut Buffer) -> KeyAction + Send>;
+
+/// What read_line does with a key after the callback set with Buffer.set_on_key().
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyAction {
+    /// the callback has handled the key, which is not processed further
+    Handled,
+    /// the key is processed by the key bindings as usual
+    Passthrough,
+    /// finish the input with the key
+    Terminate,
+}
+
+/// Editing operation, which can be bound to a key with Buffer.bind().
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Action {
+    /// move to the head of the text
+    Home,
+    /// move to the end of the text
+    End,
+    /// move left by a character
+    Left,
+    /// move right by a character
+    Right,
+    /// delete the character before the cursor
+    Backspace,
+    /// delete the character under the cursor
+    Delete,
+    /// move to the next word separator
+    WordForward,
+    /// move to the head of the previous word
+    WordBackward,
+    /// delete the word before the cursor
+    WordBackspace,
+    /// delete the word after the cursor
+    WordDelete,
+    /// delete the text from the cursor to the end
+    KillToEnd,
+    /// delete the text from the head to the cursor
+    KillToStart,
+    /// insert the character
+    Insert(char),
+    /// insert a newline
+    InsertNewline,
+    /// read the next key as a meta (Alt) command, as Escape does by default
+    Meta,
+    /// switch to the normal mode of the vi editing mode, moving left by a character
+    NormalMode,
+    /// switch to the insert mode of the vi editing mode
+    InsertMode,
+    /// move right by a character and switch to the insert mode
+    AppendMode,
+    /// move to the head of the text and switch to the insert mode
+    InsertAtHome,
+    /// move to the end of the text and switch to the insert mode
+    AppendAtEnd,
+    /// finish the input, as Enter does by default
+    Submit,
+    /// finish the input with the key, as ArrowUp does with `terminate_on_up_down`
+    Terminate,
+    /// abandon the input with the cancelled error, as Escape does with `escape_as_cancel`
+    Cancel,
+    /// abandon the empty input with the end-of-input error, as Ctrl-D does
+    Eof,
+    /// do nothing
+    Ignore,
+}
+
+/// Interpretation of the keys in read_line.
+///
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EditingMode {
+    /// Emacs-like shortcuts, where the text is always inserted
+    #[default]
+    Emacs,
+    /// vi-like modal editing, which starts in the insert mode and Escape switches to the normal mode
+    Vi,
+}
+
+/// Error of the line editing, which tells the reason why the input was not finished.
+///
+#[derive(Debug)]
+pub enum ReadlineError {
+    /// I/O error of the terminal
+    Io(io::Error),
+    /// interrupted by a signal such as Ctrl-C
+    Interrupted,
+    /// the input stream reached the end, or Ctrl-D was pressed on the empty text
+    Eof,
+    /// cancelled with the flag set by `Buffer::set_cancel`
+    Cancelled,
+}
+
+impl std::fmt::Display for ReadlineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadlineError::Io(e) => write!(f, "{}", e),
+            ReadlineError::Interrupted => write!(f, "interrupted"),
+            ReadlineError::Eof => write!(f, "end of input"),
+            ReadlineError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for ReadlineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadlineError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadlineError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            _ if is_cancelled_error(&e) => ReadlineError::Cancelled,
+            io::ErrorKind::Interrupted => ReadlineError::Interrupted,
+            io::ErrorKind::UnexpectedEof => ReadlineError::Eof,
+            _ => ReadlineError::Io(e),
+        }
+    }
+}
+
+/// Snapshot of the editable state of a Buffer, taken with Buffer.snapshot().
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EditorState {
+    text: String,
+    index: usize,
+    prefix: String,
+    rprompt: String,
+    read_only_head: String,
+    read_only_tail: String,
+    editing_mode: EditingMode,
+    vi_normal: bool,
+}
+
+impl EditorState {
+    /// Text at the snapshot.
+    ///
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Cursor index at the snapshot, in bytes of the text.
+    ///
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
 
 /// Buffer of a readline instance.
 ///
 pub struct Buffer {
     /// Debug mode or not.
     debug: bool,
-    /// Whether the read_line method result newline-containing string at the index where an enter key has been pressed.
+    /// Whether the read_line method results the text followed by a newline, when it is submitted.
+    ///
+    /// The newline is appended at the end wherever the cursor is. Set `newline_on_enter` instead
+    /// to insert newlines at the cursor and continue editing.
     pub double_line_response: bool,
     /// Whether the read_line method self.terminates or not when the ArrowUp or ArrowDown key is pressed.
     pub terminate_on_up_down: bool,
     term: Term,
     /// Cursor index for the next character input
     index: usize,
+    /// Whether the Enter key inserts a newline and continues editing, rather than finishing the input.
+    pub newline_on_enter: bool,
+    /// An additional key to finish the input, which is checked before any other key binding.
+    /// Enter, the default, inserts a newline instead while `newline_on_enter` is set.
+    pub submit_key: Key,
+    /// keys which finish the input as they are, set with set_terminators()
+    terminators: Vec<Key>,
+    /// Whether the input can be finished with an empty or whitespace-only text.
+    pub allow_empty: bool,
+    /// Whether the surrounding whitespace of the text is trimmed when the input is finished.
+    pub trim: bool,
+    /// Columns between the tab stops, to which tabs in the text are expanded (8 by default).
+    pub tab_width: usize,
+    /// Whether the text is scrolled horizontally in a single row, instead of wrapped to the following rows.
+    pub horizontal_scroll: bool,
+    /// Feedback emitted when the input is finished with Enter or the submit key (none by default).
+    pub feedback_on_submit: Option<Feedback>,
+    /// Byte index of the text at the head of the horizontally scrolled row
+    scroll: usize,
+    /// Row and column of the terminal cursor, counted from the head of the prefix
+    cursor: (usize, usize),
+    /// Row and column of the end of the drawn text
+    drawn: (usize, usize),
     /// prefix string for the input area
     prefix: String,
+    /// right prompt string for the input area
+    rprompt: String,
+    /// dimmed hint shown on the row below the input area
+    hint: String,
+    /// Interpretation of the keys, Emacs-like by default.
+    pub editing_mode: EditingMode,
+    /// Whether the vi editing mode is in the normal mode
+    vi_normal: bool,
+    /// key bindings on top of the default ones
+    keymap: HashMap<Key, Action>,
+    /// callback for each edit of the text
+    on_change: Option<OnChange>,
+    /// callback for the log lines of the debug mode, instead of stderr
+    debug_sink: Option<DebugSink>,
+    /// callback for each key before the key bindings
+    on_key: Option<OnKey>,
+    /// flag to cancel the input from another thread
+    cancel: Option<Arc<AtomicBool>>,
+    /// Fixed text shown before the editable text, which the cursor cannot move into
+    read_only_head: String,
+    /// Fixed text shown after the editable text, which the cursor cannot move into
+    read_only_tail: String,
+    /// Whether the text shown with to_string() includes the read-only head and tail.
+    pub include_read_only: bool,
+    /// Whether Escape cancels the input as in a dialog box, instead of waiting for a word command such as M-f.
+    pub escape_as_cancel: bool,
+    /// Whether the app keeps the cursor hidden, which is shown while read_line runs and hidden again after it.
+    pub cursor_hidden: bool,
+    /// Whether read_line has shown the cursor, to be restored on exit or drop
+    showing_cursor: bool,
     /// Text payload for the buffer
     text: String,
 }
 
-impl ToString for Buffer {
-    fn to_string(&self) -> String {
-        self.text.clone()
+impl std::fmt::Display for Buffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.include_read_only {
+            write!(
+                f,
+                "{}{}{}",
+                self.read_only_head, self.text, self.read_only_tail
+            )
+        } else {
+            write!(f, "{}", self.text)
+        }
     }
 }
 
 impl Drop for Buffer {
     fn drop(&mut self) {
+        // such as a panic in the on_change callback, which skips the restore in read_line
+        let _ = self.restore_cursor();
         self.double_line_response = false;
         self.index = 0;
         self.text.clear();
     }
 }
 
+/// The terminal, the key bindings and the callbacks are omitted.
+///
 impl std::fmt::Debug for Buffer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Buffer")
             .field("debug", &self.debug)
             .field("double_line_response", &self.double_line_response)
             .field("terminate_on_up_down", &self.terminate_on_up_down)
+            .field("newline_on_enter", &self.newline_on_enter)
+            .field("submit_key", &self.submit_key)
+            .field("terminators", &self.terminators)
+            .field("allow_empty", &self.allow_empty)
+            .field("trim", &self.trim)
+            .field("tab_width", &self.tab_width)
+            .field("horizontal_scroll", &self.horizontal_scroll)
+            .field("feedback_on_submit", &self.feedback_on_submit)
+            .field("editing_mode", &self.editing_mode)
+            .field("prefix", &self.prefix)
+            .field("rprompt", &self.rprompt)
+            .field("hint", &self.hint)
+            .field("read_only_head", &self.read_only_head)
+            .field("read_only_tail", &self.read_only_tail)
+            .field("include_read_only", &self.include_read_only)
+            .field("escape_as_cancel", &self.escape_as_cancel)
+            .field("cursor_hidden", &self.cursor_hidden)
+            .field("cursor", &self.cursor)
             .field("index", &self.index)
-            .field("text", &self.text);
-        Ok(())
+            .field("text", &self.text)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Buffers are equal if they have the same text and the same cursor index,
+/// regardless of the terminal and the configurations.
+///
+impl PartialEq for Buffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.index == other.index
     }
 }
 
+/// The clone shares the callbacks of set_on_change() and set_debug_sink(), but not the one of
+/// set_on_key(), which is called with the buffer mutably and can replace itself.
+///
 impl Clone for Buffer {
     fn clone(&self) -> Self {
         Self {
@@ -82,24 +350,94 @@ impl Clone for Buffer {
             terminate_on_up_down: self.terminate_on_up_down,
             term: self.term.clone(),
             index: self.index,
+            newline_on_enter: self.newline_on_enter,
+            submit_key: self.submit_key.clone(),
+            terminators: self.terminators.clone(),
+            allow_empty: self.allow_empty,
+            trim: self.trim,
+            tab_width: self.tab_width,
+            horizontal_scroll: self.horizontal_scroll,
+            feedback_on_submit: self.feedback_on_submit,
+            scroll: self.scroll,
+            cursor: self.cursor,
+            drawn: self.drawn,
             prefix: self.prefix.clone(),
+            rprompt: self.rprompt.clone(),
+            hint: self.hint.clone(),
+            editing_mode: self.editing_mode,
+            vi_normal: self.vi_normal,
+            keymap: self.keymap.clone(),
+            on_change: self.on_change.clone(),
+            debug_sink: self.debug_sink.clone(),
+            on_key: None,
+            cancel: self.cancel.clone(),
+            read_only_head: self.read_only_head.clone(),
+            read_only_tail: self.read_only_tail.clone(),
+            include_read_only: self.include_read_only,
+            escape_as_cancel: self.escape_as_cancel,
+            cursor_hidden: self.cursor_hidden,
+            showing_cursor: false,
             text: self.text.clone(),
         }
     }
 }
 
+impl Default for Buffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Buffer {
-    /// Generate blank buffer.
+    /// Generate blank buffer, which allocates no text until it is edited.
     ///
     pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Generate blank buffer with the text allocated for `capacity` bytes.
+    ///
+    /// ```rust
+    /// use ttyui::readline::Buffer;
+    /// let buf = Buffer::with_capacity(16);
+    /// assert_eq!(buf.to_string(), "");
+    /// ```
+    ///
+    pub fn with_capacity(capacity: usize) -> Self {
         Buffer {
             debug: false,
             double_line_response: false,
             terminate_on_up_down: false,
             term: Term::stdout(),
             index: 0,
-            prefix: String::with_capacity(MAX_PREFIX_CAPACITY),
-            text: String::with_capacity(DEFAULT_TEXT_CAPACITY),
+            newline_on_enter: false,
+            submit_key: Key::Enter,
+            terminators: Vec::new(),
+            allow_empty: true,
+            trim: false,
+            tab_width: DEFAULT_TAB_WIDTH,
+            horizontal_scroll: false,
+            feedback_on_submit: None,
+            scroll: 0,
+            cursor: (0, 0),
+            drawn: (0, 0),
+            prefix: String::new(),
+            rprompt: String::new(),
+            hint: String::new(),
+            editing_mode: EditingMode::Emacs,
+            vi_normal: false,
+            keymap: HashMap::new(),
+            on_change: None,
+            debug_sink: None,
+            on_key: None,
+            cancel: None,
+            read_only_head: String::new(),
+            read_only_tail: String::new(),
+            include_read_only: false,
+            escape_as_cancel: false,
+            cursor_hidden: false,
+            showing_cursor: false,
+            text: String::with_capacity(capacity),
         }
     }
 
@@ -112,94 +450,363 @@ impl Buffer {
             terminate_on_up_down: false,
             term: Term::stdout(),
             index: 0,
-            prefix: String::with_capacity(MAX_PREFIX_CAPACITY),
+            newline_on_enter: false,
+            submit_key: Key::Enter,
+            terminators: Vec::new(),
+            allow_empty: true,
+            trim: false,
+            tab_width: DEFAULT_TAB_WIDTH,
+            horizontal_scroll: false,
+            feedback_on_submit: None,
+            scroll: 0,
+            cursor: (0, 0),
+            drawn: (0, 0),
+            prefix: String::new(),
+            rprompt: String::new(),
+            hint: String::new(),
+            editing_mode: EditingMode::Emacs,
+            vi_normal: false,
+            keymap: HashMap::new(),
+            on_change: None,
+            debug_sink: None,
+            on_key: None,
+            cancel: None,
+            read_only_head: String::new(),
+            read_only_tail: String::new(),
+            include_read_only: false,
+            escape_as_cancel: false,
+            cursor_hidden: false,
+            showing_cursor: false,
             text: String::from(text),
         }
     }
 
-    /// Switch on debug mode
+    /// Generate blank buffer, which reads keys from and draws on the terminal.
+    ///
+    /// ```rust,no_run
+    /// use console::Term;
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let term = Term::stderr();
+    /// let mut buf = Buffer::with_term(term.clone());
+    /// buf.read_line().unwrap();
+    /// term.write_line("").unwrap();
+    /// ```
+    ///
+    pub fn with_term(term: Term) -> Self {
+        let mut buf = Self::new();
+        buf.term = term;
+        buf
+    }
+
+    /// Replace the terminal, for a prompt composed in the crate with the initial text.
+    ///
+    pub(crate) fn set_term(&mut self, term: Term) {
+        self.term = term;
+    }
+
+    /// Clear the text for the next input, keeping the allocated capacity and configurations.
+    ///
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.index = 0;
+        self.scroll = 0;
+        self.cursor = (0, 0);
+        self.drawn = (0, 0);
+    }
+
+    /// Insert a string at the cursor and redraw the line at once.
+    ///
+    /// Newlines in the string are kept only if `double_line_response` or `newline_on_enter` is set,
+    /// and the other control characters but tabs, such as carriage returns and escapes, are dropped.
+    ///
+    pub fn insert_str(&mut self, s: &str) -> io::Result<()> {
+        let from = self.index;
+        let s: String = s.chars().filter(|c| self.is_insertable(*c)).collect();
+        self.text.insert_str(self.index, &s);
+        self.index += s.len();
+        self.redraw_from(from)?;
+        self.changed();
+        Ok(())
+    }
+
+    /// Take a snapshot of the text, the cursor, the prompts and the editing mode.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let mut buf = Buffer::from("8080");
+    /// buf.set_prefix("port: ".to_string());
+    /// let state = buf.snapshot();
+    /// buf.read_line().unwrap();
+    /// if buf.to_string().parse::<u16>().is_err() {
+    ///     // show the previous value again in place of the rejected one
+    ///     buf.restore(state).unwrap();
+    /// }
+    /// println!();
+    /// ```
+    ///
+    pub fn snapshot(&self) -> EditorState {
+        EditorState {
+            text: self.text.clone(),
+            index: self.index,
+            prefix: self.prefix.clone(),
+            rprompt: self.rprompt.clone(),
+            read_only_head: self.read_only_head.clone(),
+            read_only_tail: self.read_only_tail.clone(),
+            editing_mode: self.editing_mode,
+            vi_normal: self.vi_normal,
+        }
+    }
+
+    /// Restore the state from a snapshot, and redraw the input area with the prefix.
+    ///
+    /// The input area is redrawn where it has been drawn, as set_text() does.
+    ///
+    pub fn restore(&mut self, state: EditorState) -> io::Result<()> {
+        self.move_to((0, 0))?;
+        self.text = state.text;
+        self.index = state.index;
+        self.prefix = state.prefix;
+        self.rprompt = state.rprompt;
+        self.read_only_head = state.read_only_head;
+        self.read_only_tail = state.read_only_tail;
+        self.editing_mode = state.editing_mode;
+        self.vi_normal = state.vi_normal;
+        self.scroll = 0;
+        write!(
+            &self.term,
+            "\r{}{}{}",
+            self.prefix,
+            self.read_only_head,
+            self.wrap_at(self.raw_position(0))
+        )?;
+        self.cursor = self.position(0);
+        self.redraw_from(0)?;
+        self.changed();
+        Ok(())
+    }
+
+    /// Replace the whole text and redraw it after the prefix, with the cursor at the end.
+    ///
+    /// The rest of the old text on the screen is cleared. This is a building block for
+    /// the history recall or the completion, e.g. after read_line() returned with ArrowUp
+    /// where `terminate_on_up_down` is set.
+    ///
+    pub fn set_text(&mut self, s: &str) -> io::Result<()> {
+        self.text.clear();
+        self.text.push_str(s);
+        self.index = self.text.len();
+        self.redraw_from(0)?;
+        self.changed();
+        Ok(())
+    }
+
+    /// Count the words in the text, which are separated as the word motions do.
+    ///
+    pub fn word_count(&self) -> usize {
+        self.text
+            .split(WORD_SEPARATOR)
+            .filter(|w| !w.is_empty())
+            .count()
+    }
+
+    /// Count the lines in the text, which is the number of newlines plus one.
+    ///
+    pub fn line_count(&self) -> usize {
+        self.text.matches('\n').count() + 1
+    }
+
+    /// Whether the character can be in the text, which is not a control character
+    /// but a tab or a newline allowed by the configuration.
+    ///
+    fn is_insertable(&self, c: char) -> bool {
+        match c {
+            '\t' => true,
+            '\n' => self.double_line_response || self.newline_on_enter,
+            _ => !c.is_control(),
+        }
+    }
+
+    /// Return the display column of the cursor, which counts the prefix and wide characters.
+    ///
+    /// For the text wrapped by the terminal width or broken by newlines,
+    /// the column is counted from the head of the row where the cursor is.
+    ///
+    pub fn cursor_column(&self) -> usize {
+        self.position(self.index).1
+    }
+
+    /// Take the keys typed ahead but not read yet, without blocking.
+    ///
+    /// Keys pressed after read_line() returned are kept in the terminal and are
+    /// normally processed by the next read. This method lets the caller decide what to do with them.
+    ///
+    pub fn drain_input(&self) -> io::Result<Vec<Key>> {
+        let mut keys = Vec::new();
+        while let Some(k) = read_key_timeout(&self.term, Duration::ZERO)? {
+            keys.push(k);
+        }
+        Ok(keys)
+    }
+
+    /// Switch on debug mode, where read_line logs each key read, whether it is handled or ignored,
+    /// the action taken for it, and the cursor index and the text after it, like
+    /// `key=Char('a') handled action=Some(Insert('a')) index=1 text="a"`.
+    ///
+    /// The lines go to stderr unless a sink is set with set_debug_sink(), so that
+    /// they can be redirected away from the terminal, such as `2>keys.log`.
     ///
     pub fn debug(&mut self) {
         self.debug = true;
     }
 
+    /// Receive the log lines of the debug mode with a callback, instead of stderr.
+    ///
+    /// ```rust,no_run
+    /// use std::io::Write;
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let mut log = std::fs::File::create("keys.log").unwrap();
+    /// let mut buf = Buffer::new();
+    /// buf.debug();
+    /// buf.set_debug_sink(move |line| {
+    ///     let _ = writeln!(&log, "{}", line);
+    /// });
+    /// buf.read_line().unwrap();
+    /// ```
+    ///
+    pub fn set_debug_sink<F: Fn(&str) + Send + Sync + 'static>(&mut self, sink: F) {
+        self.debug_sink = Some(Arc::new(sink));
+    }
+
+    /// Log a key, whether it is handled, and the action taken for it in the debug mode.
+    ///
+    /// The action is None for a key handled by a callback or as a meta key, and for an ignored key.
+    ///
+    fn log_key(&self, key: &dyn std::fmt::Debug, handled: bool, action: Option<Action>) {
+        if !self.debug {
+            return;
+        }
+        let line = format!(
+            "key={:?} {} action={:?} index={} text={:?}",
+            key,
+            if handled { "handled" } else { "ignored" },
+            action,
+            self.index,
+            self.text
+        );
+        match &self.debug_sink {
+            Some(sink) => sink(&line),
+            None => eprintln!("{}", line),
+        }
+    }
+
+    /// Whether the text can finish the input, or show a message after the text if not.
+    ///
+    /// The message is cleared by the next edit, as it is counted in the drawn area.
+    ///
+    fn accepts(&mut self) -> io::Result<bool> {
+        if self.allow_empty || !self.text.trim().is_empty() {
+            return Ok(true);
+        }
+        let end = self.end_position();
+        let w = measure_text_width(EMPTY_INPUT_MESSAGE) + 1;
+        if end.1 + w >= self.width() {
+            self.term.write_str("\x07")?;
+            return Ok(false);
+        }
+        let out = format!(
+            "{}\x1b[0J {}{}",
+            self.cursor_moves(self.cursor, end),
+            EMPTY_INPUT_MESSAGE,
+            self.cursor_moves((end.0, end.1 + w), self.cursor)
+        );
+        self.term.write_str(&out)?;
+        self.drawn = (end.0, end.1 + w);
+        Ok(false)
+    }
+    /// Trim the surrounding whitespace of the text in place, keeping the allocated capacity.
+    ///
+    fn trim_text(&mut self) {
+        let len = self.text.trim_end().len();
+        self.text.truncate(len);
+        let head = self.text.len() - self.text.trim_start().len();
+        self.text.drain(..head);
+        self.index = self.text.len();
+    }
     fn enter(&mut self) -> io::Result<Key> {
         if self.double_line_response {
-            self.text.insert(self.index, '\n');
+            self.text.push('\n');
+            self.index = self.text.len();
         }
         Ok(Key::Enter)
     }
     fn home(&mut self) -> io::Result<Key> {
-        self.term.move_cursor_left(self.index)?;
         self.index = 0;
+        self.move_cursor()?;
         Ok(Key::Home)
     }
     fn end(&mut self) -> io::Result<Key> {
-        self.term.move_cursor_right(self.text.len() - self.index)?;
         self.index = self.text.len();
+        self.move_cursor()?;
         Ok(Key::End)
     }
     fn char(&mut self, x: char) -> io::Result<Key> {
-        self.term.move_cursor_right(self.text.len() - self.index)?;
-        self.term.clear_chars(self.text.len() - self.index)?;
+        let from = self.index;
         self.text.insert(self.index, x);
-        write!(&self.term, "{}", &self.text[self.index..self.text.len()])?;
-        self.index += 1;
-        self.term.move_cursor_left(self.text.len() - self.index)?;
+        self.index += x.len_utf8();
+        self.redraw_from(from)?;
+        self.changed();
         Ok(Key::Char(x))
     }
     fn backspace(&mut self) -> io::Result<Key> {
-        if self.index > 0 {
-            self.term.clear_line()?;
-            self.term
-                .move_cursor_left(self.text.len() + self.prefix.len())?;
-            write!(&self.term, "{}", self.prefix)?;
-            self.text.remove(self.index - 1);
-            self.index -= 1;
-            write!(&self.term, "{}", self.text)?;
-            self.term.move_cursor_left(self.text.len() - self.index)?;
+        if let Some(c) = self.text[..self.index].chars().next_back() {
+            self.index -= c.len_utf8();
+            self.text.remove(self.index);
+            self.redraw_from(self.index)?;
+            self.changed();
         }
         Ok(Key::Backspace)
     }
     fn del(&mut self) -> io::Result<Key> {
-        if self.text.len() > 0 && self.text.len() > self.index {
+        if !self.text.is_empty() && self.text.len() > self.index {
             self.text.remove(self.index);
-            self.term.clear_line()?;
-            write!(&self.term, "{}", self.text)?;
-            self.term.move_cursor_left(self.text.len() - self.index)?;
+            self.redraw_from(self.index)?;
+            self.changed();
         }
         Ok(Key::Del)
     }
+    /// Read the key following Escape as a meta command, as Alt+key.
+    ///
     fn esc(&mut self) -> io::Result<()> {
-        match self.term.read_key()? {
-            Key::Char('f') => {
-                self.word_forward()?;
-            }
-            Key::Char('b') => {
-                self.word_backword()?;
-            }
-            Key::Char('d') => {
-                self.word_delete()?;
-            }
-            Key::Backspace => {
-                self.word_backspace()?;
-            }
-            _ => {}
+        let key = self.term.read_key()?;
+        self.alt(key)
+    }
+
+    /// Handle a key pressed with Alt, such as Alt+f or Alt+Left.
+    ///
+    fn alt(&mut self, key: Key) -> io::Result<()> {
+        match key {
+            Key::Char('f') | Key::ArrowRight => self.word_forward(),
+            Key::Char('b') | Key::ArrowLeft => self.word_backword(),
+            Key::Char('d') => self.word_delete(),
+            Key::Backspace => self.word_backspace(),
+            _ => Ok(()),
         }
-        Ok(())
     }
 
     fn word_forward(&mut self) -> io::Result<()> {
         let mut separater_ids = self
             .text
-            .match_indices(' ')
+            .match_indices(WORD_SEPARATOR)
             .map(|t| t.0)
             .collect::<Vec<usize>>();
         separater_ids.push(self.text.len());
         for i in separater_ids {
             if i > self.index {
-                self.term.move_cursor_right(i - self.index)?;
                 self.index = i;
+                self.move_cursor()?;
                 break;
             }
         }
@@ -209,15 +816,15 @@ impl Buffer {
     fn word_backword(&mut self) -> io::Result<()> {
         let mut separater_ids = self
             .text
-            .match_indices(' ')
+            .match_indices(WORD_SEPARATOR)
             .map(|t| t.0 + 1)
             .collect::<Vec<usize>>();
         separater_ids.insert(0, 0);
         separater_ids.reverse();
         for i in separater_ids {
             if self.index > i {
-                self.term.move_cursor_left(self.index - i)?;
                 self.index = i;
+                self.move_cursor()?;
                 break;
             }
         }
@@ -227,23 +834,22 @@ impl Buffer {
     fn word_backspace(&mut self) -> io::Result<()> {
         let mut separater_ids = self
             .text
-            .match_indices(' ')
+            .match_indices(WORD_SEPARATOR)
             .map(|t| t.0)
             .filter(|n| *n < self.index)
             .collect::<Vec<usize>>();
         separater_ids.insert(0, 0);
         separater_ids.reverse();
 
-        if self.text.len() != 0 {
+        if !self.text.is_empty() {
             let target_id = separater_ids[0];
             let new_text =
                 self.text[0..target_id].to_string() + &self.text[self.index..self.text.len()];
             self.text.clear();
             self.text = new_text;
             self.index = target_id;
-            self.term.clear_line()?;
-            write!(&self.term, "{}", self.text)?;
-            self.term.move_cursor_left(self.text.len() - target_id)?;
+            self.redraw_from(target_id)?;
+            self.changed();
         }
 
         Ok(())
@@ -252,7 +858,7 @@ impl Buffer {
     fn word_delete(&mut self) -> io::Result<()> {
         let mut separater_ids = self
             .text
-            .match_indices(' ')
+            .match_indices(WORD_SEPARATOR)
             .map(|t| t.0)
             .filter(|n| *n > self.index)
             .collect::<Vec<usize>>();
@@ -262,99 +868,838 @@ impl Buffer {
             self.text[0..self.index].to_string() + &self.text[target_id..self.text.len()];
         self.text.clear();
         self.text = new_text;
-        self.term.clear_line()?;
-        write!(&self.term, "{}", self.text)?;
-        self.term.move_cursor_left(self.text.len() - self.index)?;
+        self.redraw_from(self.index)?;
+        self.changed();
         Ok(())
     }
 
-    fn left(&mut self) -> io::Result<Key> {
+    fn kill_to_end(&mut self) -> io::Result<()> {
+        if self.index < self.text.len() {
+            self.text.truncate(self.index);
+            self.redraw_from(self.index)?;
+            self.changed();
+        }
+        Ok(())
+    }
+
+    fn kill_to_start(&mut self) -> io::Result<()> {
         if self.index > 0 {
-            self.term.move_cursor_left(1)?;
-            self.index -= 1;
+            self.text.drain(..self.index);
+            self.index = 0;
+            self.redraw_from(0)?;
+            self.changed();
+        }
+        Ok(())
+    }
+
+    fn left(&mut self) -> io::Result<Key> {
+        if let Some(c) = self.text[..self.index].chars().next_back() {
+            self.index -= c.len_utf8();
+            self.move_cursor()?;
         }
         Ok(Key::ArrowLeft)
     }
     fn right(&mut self) -> io::Result<Key> {
-        if self.index < self.text.len() {
-            self.term.move_cursor_right(1)?;
-            self.index += 1;
+        if let Some(c) = self.text[self.index..].chars().next() {
+            self.index += c.len_utf8();
+            self.move_cursor()?;
+        }
+        Ok(Key::ArrowRight)
+    }
+
+    /// Width of the terminal, in columns.
+    ///
+    fn width(&self) -> usize {
+        (self.term.size().1 as usize).max(1)
+    }
+
+    /// Row and column where the text up to the byte index `i` ends, counted from the head of the prefix.
+    ///
+    /// The column reaches the terminal width when a row is just filled up,
+    /// where a terminal keeps the cursor on the last column until the next character comes.
+    ///
+    fn raw_position(&self, i: usize) -> (usize, usize) {
+        let width = self.width();
+        strip_ansi_codes(&self.prefix)
+            .chars()
+            .chain(self.read_only_head.chars())
+            .chain(self.text[..i].chars())
+            .fold((0, 0), |p, c| self.advance(p, c, width))
+    }
+
+    /// Row and column after the character `c` is written at the position `p`.
+    ///
+    /// A tab advances to the next multiple of `tab_width`, but not beyond the row.
+    ///
+    fn advance(&self, p: (usize, usize), c: char, width: usize) -> (usize, usize) {
+        let (row, col) = p;
+        match c {
+            '\n' => (row + 1, 0),
+            '\t' => {
+                let (row, col) = if col >= width { (row + 1, 0) } else { p };
+                let t = self.tab_width.max(1);
+                (row, col + (t - col % t).min(width - col))
+            }
+            _ => {
+                let w = measure_text_width(c.encode_utf8(&mut [0; 4]));
+                if col + w > width {
+                    (row + 1, w)
+                } else {
+                    (row, col + w)
+                }
+            }
+        }
+    }
+
+    /// Text after the byte index `from` to be written on the terminal.
+    ///
+    /// Tabs are expanded to spaces, so that they take the columns counted by `advance`
+    /// regardless of the tab stops of the terminal.
+    ///
+    fn rendered_from(&self, from: usize) -> String {
+        let width = self.width();
+        let mut p = self.raw_position(from);
+        let mut out = String::new();
+        for c in self.text[from..].chars().chain(self.read_only_tail.chars()) {
+            let next = self.advance(p, c, width);
+            match c {
+                // clear the rest of each row before a line break
+                '\n' => out += "\x1b[K\n",
+                '\t' => {
+                    let col = if p.1 >= width { 0 } else { p.1 };
+                    out += &" ".repeat(next.1 - col);
+                }
+                _ => out.push(c),
+            }
+            p = next;
+        }
+        out
+    }
+
+    /// Row and column of the cursor placed at the byte index `i` of the text.
+    ///
+    fn position(&self, i: usize) -> (usize, usize) {
+        self.wrapped(self.raw_position(i))
+    }
+
+    /// Position `p` pushed to the head of the next row if it is beyond the last column.
+    ///
+    fn wrapped(&self, p: (usize, usize)) -> (usize, usize) {
+        match p {
+            (row, col) if col >= self.width() => (row + 1, 0),
+            p => p,
+        }
+    }
+
+    /// Row and column where the read-only tail after the text ends.
+    ///
+    fn raw_end(&self) -> (usize, usize) {
+        let width = self.width();
+        self.read_only_tail
+            .chars()
+            .fold(self.raw_position(self.text.len()), |p, c| {
+                self.advance(p, c, width)
+            })
+    }
+
+    /// Escape sequence to move the cursor from the position `from` to `to`.
+    ///
+    fn cursor_moves(&self, from: (usize, usize), to: (usize, usize)) -> String {
+        let ((from_row, from_col), (to_row, to_col)) = (from, to);
+        let mut seq = String::new();
+        if to_row < from_row {
+            seq += &format!("\x1b[{}A", from_row - to_row);
+        } else if to_row > from_row {
+            seq += &format!("\x1b[{}B", to_row - from_row);
+        }
+        if to_col < from_col {
+            seq += &format!("\x1b[{}D", from_col - to_col);
+        } else if to_col > from_col {
+            seq += &format!("\x1b[{}C", to_col - from_col);
+        }
+        seq
+    }
+
+    /// Move the cursor to the position `to`.
+    ///
+    fn move_to(&mut self, to: (usize, usize)) -> io::Result<()> {
+        self.term.write_str(&self.cursor_moves(self.cursor, to))?;
+        self.cursor = to;
+        Ok(())
+    }
+
+    /// Move the cursor to `self.index`, scrolling the row if `horizontal_scroll` is set.
+    ///
+    fn move_cursor(&mut self) -> io::Result<()> {
+        if self.horizontal_scroll {
+            self.redraw_row()
+        } else {
+            self.move_to(self.position(self.index))
+        }
+    }
+
+    /// Position where the drawn text ends.
+    ///
+    fn end_position(&self) -> (usize, usize) {
+        if self.horizontal_scroll {
+            (0, self.window().3)
+        } else {
+            self.wrapped(self.raw_end())
+        }
+    }
+
+    /// Visible part of the horizontally scrolled text, which keeps the cursor in view.
+    ///
+    /// It returns the byte index of the head of the window, the text to be written after the prefix,
+    /// and the columns of the cursor and the end. Both cut ends are marked with an ellipsis,
+    /// and the last column of the row is left blank.
+    ///
+    fn window(&self) -> (usize, String, usize, usize) {
+        let start = measure_text_width(&self.prefix) + measure_text_width(&self.read_only_head);
+        // columns for the text, the last of which is kept for the right marker
+        let avail = self.width().saturating_sub(start + 1).max(3);
+        let char_width = |c: char| match c {
+            '\t' => self.tab_width.max(1),
+            '\n' => 1,
+            _ => measure_text_width(c.encode_utf8(&mut [0; 4])),
+        };
+        let cols = |s: &str| -> usize { s.chars().map(char_width).sum() };
+
+        let mut head = self.scroll.min(self.index);
+        while !self.text.is_char_boundary(head) {
+            head -= 1;
+        }
+        while (head > 0) as usize + cols(&self.text[head..self.index]) >= avail - 1 {
+            match self.text[head..].chars().next() {
+                Some(c) => head += c.len_utf8(),
+                None => break,
+            }
+        }
+
+        let mut shown = String::new();
+        let mut col = 0;
+        if head > 0 {
+            shown.push('…');
+            col += 1;
+        }
+        let cursor = col + cols(&self.text[head..self.index]);
+        for c in self.text[head..].chars().chain(self.read_only_tail.chars()) {
+            let w = char_width(c);
+            if col + w > avail - 1 {
+                shown.push('…');
+                col += 1;
+                break;
+            }
+            match c {
+                '\t' => shown += &" ".repeat(w),
+                '\n' => shown.push('↵'),
+                _ => shown.push(c),
+            }
+            col += w;
+        }
+        (head, shown, start + cursor, start + col)
+    }
+
+    /// Rewrite the row for `horizontal_scroll`, with the window scrolled to keep the cursor in view.
+    ///
+    fn redraw_row(&mut self) -> io::Result<()> {
+        let (head, shown, cursor, end) = self.window();
+        let mut out = format!("\r{}{}{}\x1b[K\r", self.prefix, self.read_only_head, shown);
+        if !self.hint.is_empty() {
+            out += &self.hint_row();
+            out += "\x1b[A";
+        }
+        if cursor > 0 {
+            out += &format!("\x1b[{}C", cursor);
+        }
+        self.term.write_str(&out)?;
+        self.scroll = head;
+        self.cursor = (0, cursor);
+        self.drawn = (0, end);
+        Ok(())
+    }
+
+    /// Move the cursor after the text, not to overwrite the following rows, and clear the hint below it.
+    ///
+    fn leave(&mut self) -> io::Result<()> {
+        self.move_to(self.end_position())?;
+        if !self.hint.is_empty() {
+            self.term.write_str("\x1b[0J")?;
+        }
+        Ok(())
+    }
+
+    /// Hint written on the row below the cursor, fitted into the row not to be wrapped,
+    /// with the cursor left at the head of the row.
+    ///
+    fn hint_row(&self) -> String {
+        let hint = truncate_str(&self.hint, self.width().saturating_sub(1), "…");
+        format!("\r\n\x1b[2m{}\x1b[0m\x1b[K\r", hint)
+    }
+
+    /// Line break to be written after the text which ends at the raw position `p`.
+    ///
+    /// The cursor left on the last column of a filled row is pushed to the head of the next row,
+    /// to keep it consistent with `position`.
+    ///
+    fn wrap_at(&self, p: (usize, usize)) -> &'static str {
+        if p.1 >= self.width() {
+            "\n"
+        } else {
+            ""
+        }
+    }
+
+    /// Column to start the right prompt, for the text ending at `end`.
+    ///
+    /// The right prompt leaves the last column blank and is hidden when the text
+    /// reaches it or the terminal is too narrow.
+    ///
+    fn rprompt_column(&self, end: (usize, usize)) -> Option<usize> {
+        let w = measure_text_width(&self.rprompt);
+        if self.rprompt.is_empty() || end.0 != 0 || end.1 + w + 2 > self.width() {
+            None
+        } else {
+            Some(self.width() - 1 - w)
+        }
+    }
+
+    /// Rewrite the text after the byte index `from` and put the cursor back on `self.index`.
+    ///
+    /// Only the changed part of the line is written, with a single write to the terminal.
+    /// The leftover of a longer text is cleared only if the text has been shortened.
+    ///
+    fn redraw_from(&mut self, from: usize) -> io::Result<()> {
+        if self.horizontal_scroll {
+            return self.redraw_row();
+        }
+        let end = self.wrapped(self.raw_end());
+        let to = self.position(self.index);
+        let mut out = self.cursor_moves(self.cursor, self.position(from));
+        if from < self.text.len() || !self.read_only_tail.is_empty() {
+            out += &self.rendered_from(from);
+            out += self.wrap_at(self.raw_end());
+        }
+        if end < self.drawn || !self.rprompt.is_empty() || !self.hint.is_empty() {
+            // clear the rest of the rows, without moving the cursor to the line head
+            out += "\x1b[0J";
+        }
+        let mut last = end;
+        if let Some(col) = self.rprompt_column(end) {
+            out += &self.cursor_moves(end, (0, col));
+            out += &self.rprompt;
+            last = (0, col + measure_text_width(&self.rprompt));
+        }
+        if !self.hint.is_empty() {
+            out += &self.hint_row();
+            last = (last.0 + 1, 0);
+        }
+        out += &self.cursor_moves(last, to);
+        self.term.write_str(&out)?;
+        self.cursor = to;
+        self.drawn = end;
+        Ok(())
+    }
+
+    /// set prefix for the input area
+    pub fn set_prefix(&mut self, prefix: String) {
+        self.prefix.clear();
+        self.prefix = prefix;
+    }
+
+    /// Register a callback which is called with the text after each edit, such as insertion,
+    /// deletion and word operations.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let mut buf = Buffer::new();
+    /// buf.set_on_change(|text| eprint!("\r{} chars", text.chars().count()));
+    /// buf.read_line().unwrap();
+    /// ```
+    ///
+    pub fn set_on_change<F: Fn(&str) + Send + Sync + 'static>(&mut self, on_change: F) {
+        self.on_change = Some(Arc::new(on_change));
+    }
+
+    /// Register a callback which intercepts each key read by read_line, before the submit key
+    /// and the key bindings, with the buffer to edit.
+    ///
+    /// The returned KeyAction tells whether the key is processed as usual, or finishes the input.
+    /// The callback is not cloned with the buffer, so a clone reads keys without it.
+    ///
+    /// ```rust,no_run
+    /// use console::Key;
+    /// use ttyui::readline::{Buffer, KeyAction};
+    ///
+    /// let mut buf = Buffer::new();
+    /// buf.set_on_key(|key, buf| match key {
+    ///     // Ctrl-U upcases the text
+    ///     Key::Char('\x15') => {
+    ///         let upper = buf.to_string().to_uppercase();
+    ///         buf.set_text(&upper).unwrap();
+    ///         KeyAction::Handled
+    ///     }
+    ///     Key::Tab => KeyAction::Terminate,
+    ///     _ => KeyAction::Passthrough,
+    /// });
+    /// buf.read_line().unwrap();
+    /// ```
+    ///
+    pub fn set_on_key<F: FnMut(Key, &mut Buffer) -> KeyAction + Send + 'static>(
+        &mut self,
+        on_key: F,
+    ) {
+        self.on_key = Some(Box::new(on_key));
+    }
+
+    /// Pass a key to the callback set with set_on_key(), or Passthrough without it.
+    ///
+    /// The callback is taken out while it runs, so that it can edit the buffer and even
+    /// set another callback, which replaces it.
+    ///
+    fn intercept(&mut self, key: &Key) -> KeyAction {
+        match self.on_key.take() {
+            Some(mut on_key) => {
+                let action = on_key(key.clone(), self);
+                if self.on_key.is_none() {
+                    self.on_key = Some(on_key);
+                }
+                action
+            }
+            None => KeyAction::Passthrough,
+        }
+    }
+
+    /// Notify the change of the text to the callback.
+    ///
+    fn changed(&self) {
+        if let Some(f) = &self.on_change {
+            f(&self.text);
+        }
+    }
+
+    /// Set a flag to cancel the input from another thread.
+    ///
+    /// The flag is checked while waiting for a key, and read_line() results
+    /// io::ErrorKind::Interrupted when it is set, leaving the text as it is.
+    ///
+    /// ```rust,no_run
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use std::{thread, time::Duration};
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let flag = Arc::new(AtomicBool::new(false));
+    /// let f = flag.clone();
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(5));
+    ///     f.store(true, Ordering::Relaxed);
+    /// });
+    /// let mut buf = Buffer::new();
+    /// buf.set_cancel(flag);
+    /// println!("{:?}", buf.read_line());
+    /// ```
+    ///
+    pub fn set_cancel(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel = Some(flag);
+    }
+
+    /// Set the fixed text around the editable text, such as `https://` and `/` for a host name.
+    ///
+    /// The read-only head and tail are shown with the text, but the cursor cannot move into them
+    /// and they are never deleted. The text shown with to_string() is the editable part only,
+    /// unless `include_read_only` is set.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let mut buf = Buffer::new();
+    /// buf.set_read_only("https://", "/index.html");
+    /// buf.read_line().unwrap();
+    /// println!("\nhost: {}", buf);
+    /// buf.include_read_only = true;
+    /// println!("url: {}", buf);
+    /// ```
+    ///
+    pub fn set_read_only(&mut self, head: &str, tail: &str) {
+        self.read_only_head = head.to_string();
+        self.read_only_tail = tail.to_string();
+    }
+
+    /// set right prompt, which is shown flush-right on the input line but not a part of the text
+    pub fn set_rprompt(&mut self, rprompt: String) {
+        self.rprompt = rprompt;
+    }
+
+    /// Set a hint shown dimmed on the row below the input area while read_line runs,
+    /// such as `Enter your email address`.
+    ///
+    /// The hint follows the input as it wraps, and is cleared when the input is finished.
+    /// It is not a part of the text.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let mut buf = Buffer::new();
+    /// buf.set_prefix("email: ".to_string());
+    /// buf.set_hint("Enter your email address".to_string());
+    /// buf.read_line().unwrap();
+    /// println!("\n{}", buf);
+    /// ```
+    ///
+    pub fn set_hint(&mut self, hint: String) {
+        self.hint = hint;
+    }
+
+    /// Bind a key to an action, which takes precedence over the default binding of the key.
+    ///
+    /// The submit key is still checked before any binding.
+    ///
+    /// ```rust,no_run
+    /// use console::Key;
+    /// use ttyui::readline::{Action, Buffer};
+    ///
+    /// let mut buf = Buffer::new();
+    /// // Ctrl-W
+    /// buf.bind(Key::Char('\x17'), Action::WordBackspace);
+    /// buf.read_line().unwrap();
+    /// ```
+    ///
+    pub fn bind(&mut self, key: Key, action: Action) {
+        self.keymap.insert(key, action);
+    }
+
+    /// Replace the key bindings on top of the default ones.
+    ///
+    pub fn set_keymap(&mut self, keymap: HashMap<Key, Action>) {
+        self.keymap = keymap;
+    }
+
+    /// Set the keys which finish the input, such as Tab to move to the next field or Escape to cancel,
+    /// taking precedence over the key bindings.
+    ///
+    /// Enter and the submit key still finish the input, and read_line_full() tells which key did.
+    ///
+    pub fn set_terminators(&mut self, keys: Vec<Key>) {
+        self.terminators = keys;
+    }
+
+    /// Action for a key, looked up in the submit key, the terminators, the key bindings
+    /// and the default ones in order.
+    ///
+    fn action(&self, key: &Key) -> Option<Action> {
+        if *key == self.submit_key && !(self.newline_on_enter && *key == Key::Enter) {
+            Some(Action::Submit)
+        } else if self.terminators.contains(key) {
+            Some(Action::Terminate)
+        } else {
+            self.keymap
+                .get(key)
+                .copied()
+                .or_else(|| self.default_action(key))
+        }
+    }
+
+    /// Action bound to a key by default.
+    ///
+    fn default_action(&self, key: &Key) -> Option<Action> {
+        if self.escape_as_cancel && *key == Key::Escape {
+            return Some(Action::Cancel);
+        }
+        if self.editing_mode == EditingMode::Vi {
+            if let Some(action) = self.vi_action(key) {
+                return Some(action);
+            }
+        }
+        match key {
+            Key::Enter if self.newline_on_enter => Some(Action::InsertNewline),
+            Key::Enter => Some(Action::Submit),
+            Key::Home => Some(Action::Home),
+            Key::End => Some(Action::End),
+            // the control characters as well, which console maps to Home and End only on unix
+            #[cfg(not(unix))]
+            Key::Char('\x01') => Some(Action::Home),
+            #[cfg(not(unix))]
+            Key::Char('\x05') => Some(Action::End),
+            Key::ArrowRight | Key::Char('\x06') => Some(Action::Right),
+            Key::ArrowLeft | Key::Char('\x02') => Some(Action::Left),
+            Key::Backspace => Some(Action::Backspace),
+            Key::Del => Some(Action::Delete),
+            Key::Char('\x04') if self.text.is_empty() => Some(Action::Eof),
+            Key::Char(x) if self.is_insertable(*x) => Some(Action::Insert(*x)),
+            Key::Tab => Some(Action::Insert('\t')),
+            Key::Escape => Some(Action::Meta),
+            Key::ArrowUp | Key::ArrowDown if self.terminate_on_up_down => Some(Action::Terminate),
+            _ => None,
+        }
+    }
+
+    /// Action bound to a key in the vi editing mode, where it differs from the Emacs-like one.
+    ///
+    fn vi_action(&self, key: &Key) -> Option<Action> {
+        match key {
+            Key::Escape if self.vi_normal => Some(Action::Ignore),
+            Key::Escape => Some(Action::NormalMode),
+            Key::Backspace if self.vi_normal => Some(Action::Left),
+            Key::Char(c) if self.vi_normal => Some(match c {
+                'h' => Action::Left,
+                'l' => Action::Right,
+                'w' => Action::WordForward,
+                'b' => Action::WordBackward,
+                'x' => Action::Delete,
+                'X' => Action::Backspace,
+                'D' => Action::KillToEnd,
+                '0' => Action::Home,
+                '$' => Action::End,
+                'i' => Action::InsertMode,
+                'a' => Action::AppendMode,
+                'I' => Action::InsertAtHome,
+                'A' => Action::AppendAtEnd,
+                _ => Action::Ignore,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Perform an action for the key, and return the key if it finishes the input.
+    ///
+    fn perform(&mut self, action: Action, key: &Key) -> io::Result<Option<Key>> {
+        match action {
+            Action::Home => {
+                self.home()?;
+            }
+            Action::End => {
+                self.end()?;
+            }
+            Action::Left => {
+                self.left()?;
+            }
+            Action::Right => {
+                self.right()?;
+            }
+            Action::Backspace => {
+                self.backspace()?;
+            }
+            Action::Delete => {
+                self.del()?;
+            }
+            Action::WordForward => self.word_forward()?,
+            Action::WordBackward => self.word_backword()?,
+            Action::WordBackspace => self.word_backspace()?,
+            Action::WordDelete => self.word_delete()?,
+            Action::KillToEnd => self.kill_to_end()?,
+            Action::KillToStart => self.kill_to_start()?,
+            Action::Insert(x) => {
+                self.char(x)?;
+            }
+            Action::InsertNewline => {
+                self.char('\n')?;
+            }
+            Action::Meta => self.esc()?,
+            Action::NormalMode => {
+                self.vi_normal = true;
+                self.left()?;
+            }
+            Action::InsertMode => self.vi_normal = false,
+            Action::AppendMode => {
+                self.vi_normal = false;
+                self.right()?;
+            }
+            Action::InsertAtHome => {
+                self.vi_normal = false;
+                self.home()?;
+            }
+            Action::AppendAtEnd => {
+                self.vi_normal = false;
+                self.end()?;
+            }
+            Action::Submit if !self.accepts()? => {}
+            Action::Submit => {
+                self.enter()?;
+                return Ok(Some(key.clone()));
+            }
+            Action::Terminate => return Ok(Some(key.clone())),
+            Action::Cancel => {
+                self.leave()?;
+                return Err(cancelled());
+            }
+            Action::Eof => {
+                self.leave()?;
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "end of input"));
+            }
+            Action::Ignore => {}
         }
-        Ok(Key::ArrowRight)
-    }
-
-    /// set prefix for the input area
-    pub fn set_prefix(&mut self, prefix: String) {
-        self.prefix.clear();
-        self.prefix = prefix;
+        Ok(None)
     }
 
     ///Buffer.read_line provides interactive line editing functionality for a tty, which supports following basic shortcut keys:
     ///
     /// * C-a (Home)
     /// * C-e (End)
+    /// * C-b (Left)
+    /// * C-f (Right)
     /// * M-d (word delete)
     /// * M-f (word forward)
     /// * M-b (word backward)
+    /// * M-Backspace (word backspace)
+    /// * M-Right / M-Left (word forward / backward)
+    ///
+    /// Printable characters and Tab are inserted, including the numeric keypad in the application mode.
+    /// Insert, PageUp, PageDown, BackTab, the other control characters and the unknown keys are
+    /// ignored, as are ArrowUp and ArrowDown unless `terminate_on_up_down` is set.
+    ///
+    /// Enter on an empty or whitespace-only text is rejected with a message if `allow_empty` is false.
+    ///
+    /// With `escape_as_cancel` set, Escape results io::ErrorKind::Interrupted, which try_read_line()
+    /// tells as ReadlineError::Cancelled.
+    ///
+    /// Ctrl-D on the empty text results io::ErrorKind::UnexpectedEof, which try_read_line()
+    /// tells as ReadlineError::Eof.
+    ///
+    /// With `editing_mode` set to EditingMode::Vi, Escape switches to the normal mode, where
+    /// `h`/`l` move, `w`/`b` move by a word, `x`/`X` delete, `D` deletes to the end, `0`/`$` go home/end,
+    /// and `i`/`a`/`I`/`A` switch back to the insert mode.
+    ///
+    /// The input area is expected to start at the head of a line.
+    /// Text longer than the terminal width is wrapped to the following rows, or scrolled
+    /// horizontally in a row if `horizontal_scroll` is set, where the right prompt is not shown.
+    ///
+    /// The cursor is shown while editing, and hidden again on exit if `cursor_hidden` is set.
     ///
     pub fn read_line(&mut self) -> io::Result<Key> {
-        let k: Key;
-
-        write!(&self.term, "{}", self.prefix)?;
-        loop {
-            match self.term.read_key()? {
-                Key::Enter => {
-                    k = self.enter()?;
-                    break;
-                }
-                Key::Home => {
-                    self.home()?;
-                }
-                Key::End => {
-                    self.end()?;
-                }
-                Key::ArrowRight => {
-                    self.right()?;
-                }
-                Key::ArrowLeft => {
-                    self.left()?;
-                }
-                Key::Backspace => {
-                    self.backspace()?;
-                }
-                Key::Del => {
-                    self.del()?;
-                }
-                Key::Char(x) => {
-                    self.char(x)?;
-                }
-                Key::Escape => {
-                    self.esc()?;
+        self.term.show_cursor()?;
+        self.showing_cursor = true;
+        let res = self.edit_line();
+        let restore = self.restore_cursor();
+        let key = res?;
+        restore?;
+        Ok(key)
+    }
+
+    /// Hide the cursor shown by read_line again, if the app keeps it hidden.
+    ///
+    fn restore_cursor(&mut self) -> io::Result<()> {
+        if !std::mem::take(&mut self.showing_cursor) || !self.cursor_hidden {
+            return Ok(());
+        }
+        self.term.hide_cursor()
+    }
+
+    /// Body of read_line, between showing the cursor and restoring it.
+    ///
+    fn edit_line(&mut self) -> io::Result<Key> {
+        self.vi_normal = false;
+        self.cursor = self.position(0);
+        self.drawn = self.cursor;
+        write!(
+            &self.term,
+            "{}{}{}",
+            self.prefix,
+            self.read_only_head,
+            self.wrap_at(self.raw_position(0))
+        )?;
+        self.redraw_from(0)?;
+        let k = loop {
+            let key = match read_key_or_resize(&self.term, self.cancel.as_deref()) {
+                Ok(Some(key)) => key,
+                Ok(None) => continue,
+                Err(e) => {
+                    self.leave()?;
+                    return Err(e);
                 }
-                Key::ArrowUp => {
-                    if self.terminate_on_up_down {
-                        k = Key::ArrowUp;
-                        break;
-                    }
+            };
+            match self.intercept(&key) {
+                KeyAction::Handled => {
+                    self.log_key(&key, true, None);
+                    continue;
                 }
-                Key::ArrowDown => {
-                    if self.terminate_on_up_down {
-                        k = Key::ArrowDown;
-                        break;
+                KeyAction::Terminate => break key,
+                KeyAction::Passthrough => {}
+            }
+            let (action, key) = match (self.action(&key), key) {
+                (Some(action), key) => (action, key),
+                (None, key @ Key::UnknownEscSeq(_)) => match normalize(&self.term, key)? {
+                    NormalizedKey::Alt(key) => {
+                        self.alt(key.clone())?;
+                        self.log_key(&NormalizedKey::Alt(key), true, None);
+                        continue;
                     }
+                    // such as the numeric keypad in the application mode
+                    NormalizedKey::Key(key) => match self.action(&key) {
+                        Some(action) => (action, key),
+                        None => {
+                            self.log_key(&key, false, None);
+                            continue;
+                        }
+                    },
+                },
+                (None, key) => {
+                    self.log_key(&key, false, None);
+                    continue;
                 }
-                _ => {}
+            };
+            let done = self.perform(action, &key)?;
+            self.log_key(&key, true, Some(action));
+            if let Some(k) = done {
+                break k;
             }
+        };
+        self.leave()?;
+        if let Some(feedback) = self.feedback_on_submit {
+            if k == Key::Enter || k == self.submit_key {
+                feedback.emit(&self.term)?;
+            }
+        }
+        if self.trim {
+            self.trim_text();
         }
         Ok(k)
     }
+
+    /// Buffer.read_line with the error classified into ReadlineError.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::readline::{Buffer, ReadlineError};
+    ///
+    /// let mut buf = Buffer::new();
+    /// match buf.try_read_line() {
+    ///     Ok(_) => println!("\n{}", buf),
+    ///     Err(ReadlineError::Interrupted) => println!("\ninterrupted"),
+    ///     Err(e) => eprintln!("\n{}", e),
+    /// }
+    /// ```
+    ///
+    pub fn try_read_line(&mut self) -> Result<Key, ReadlineError> {
+        Ok(self.read_line()?)
+    }
+
+    /// Buffer.read_line returning the text with the key which finished the input.
+    ///
+    /// ```rust,no_run
+    /// use console::Key;
+    /// use ttyui::readline::Buffer;
+    ///
+    /// let mut buf = Buffer::new();
+    /// buf.set_terminators(vec![Key::Tab, Key::Escape]);
+    /// match buf.read_line_full().unwrap() {
+    ///     (_, Key::Escape) => println!("\ncancelled"),
+    ///     (text, Key::Tab) => println!("\nnext field after {}", text),
+    ///     (text, _) => println!("\nsubmitted {}", text),
+    /// }
+    /// ```
+    ///
+    pub fn read_line_full(&mut self) -> io::Result<(String, Key)> {
+        let key = self.read_line()?;
+        Ok((self.to_string(), key))
+    }
 }
 
 /// A shortcut to Buffer.read_line()?.to_string.
 ///
 /// Its response contains no newline.
 ///
-/// ```rust
+/// ```rust,no_run
 /// use ttyui::readline::read_line;
 /// println!("\n\n[output]\n\x1b[33m{}\x1b[0m", read_line().unwrap());
 /// ```
@@ -365,9 +1710,40 @@ pub fn read_line() -> io::Result<String> {
     Ok(buf.to_string())
 }
 
+/// A shortcut to Buffer.read_line()?.to_string, with the surrounding whitespace trimmed.
+///
+/// ```rust,no_run
+/// use ttyui::readline::read_line_trimmed;
+/// println!("\n\n[output]\n\x1b[33m{}\x1b[0m", read_line_trimmed().unwrap());
+/// ```
+///
+pub fn read_line_trimmed() -> io::Result<String> {
+    let mut buf = Buffer::new();
+    buf.trim = true;
+    buf.read_line()?;
+    Ok(buf.to_string())
+}
+
+/// A shortcut to Buffer.read_line()?.to_string, with a prompt in front of the input area.
+///
+/// The prefix can be styled, where the escape sequences take no columns.
+///
+/// ```rust,no_run
+/// use ttyui::readline::read_line_with_prefix;
+/// let name = read_line_with_prefix("\x1b[1mname\x1b[0m: ").unwrap();
+/// println!("\nhello, {}", name);
+/// ```
+///
+pub fn read_line_with_prefix(prefix: &str) -> io::Result<String> {
+    let mut buf = Buffer::new();
+    buf.set_prefix(prefix.to_string());
+    buf.read_line()?;
+    Ok(buf.to_string())
+}
+
 /// A shortcut to Buffer.read_line()?.to_string, but returns double line (which contains newline character in the response).
 ///
-/// ```rust
+/// ```rust,no_run
 /// use ttyui::readline::read_line2;
 /// println!("\n\n[output]\n\x1b[33m{}\x1b[0m", read_line2().unwrap());
 /// ```
@@ -386,6 +1762,12 @@ mod tests {
     const DUMMY_TEXT: &str = "okachimachi koshigaya inogashira suidobashi ochanomidzu";
     const DUMMY_INDEX: usize = 19;
 
+    #[test]
+    fn test_with_capacity_allocates_text() {
+        assert_eq!(Buffer::new().text.capacity(), 0);
+        assert!(Buffer::with_capacity(64).text.capacity() >= 64);
+    }
+
     fn init_with_word() -> Buffer {
         let mut buf = Buffer::new();
         buf.text = "kabukiza".to_string();
@@ -540,7 +1922,7 @@ mod tests {
         b.word_backword().unwrap();
         assert_eq!(
             b.index,
-            DUMMY_TEXT.match_indices(' ').map(|t| t.0).nth(0).unwrap() + 1
+            DUMMY_TEXT.match_indices(' ').map(|t| t.0).next().unwrap() + 1
         );
     }
 
@@ -552,8 +1934,7 @@ mod tests {
         let idx_prev_space: usize = DUMMY_TEXT
             .match_indices(' ')
             .map(|t| t.0)
-            .filter(|n| *n < idx_init)
-            .last()
+            .rfind(|n| *n < idx_init)
             .unwrap();
         b.word_backspace().unwrap();
         assert_eq!(b.index, idx_prev_space);
@@ -575,8 +1956,7 @@ mod tests {
         let idx_next_space: usize = DUMMY_TEXT
             .match_indices(' ')
             .map(|t| t.0)
-            .filter(|n| *n >= idx_init)
-            .nth(0)
+            .find(|n| *n >= idx_init)
             .unwrap();
         b.word_delete().unwrap();
         assert_eq!(b.index, idx_init);
@@ -590,6 +1970,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_alt_sequences_move_and_delete_words() {
+        let mut b = init_modifying_buffer();
+        b.alt(Key::Char('f')).unwrap();
+        let idx_next_space = DUMMY_TEXT.match_indices(' ').map(|t| t.0).nth(1).unwrap();
+        assert_eq!(b.index, idx_next_space);
+        b.alt(Key::Char('b')).unwrap();
+        assert_eq!(b.index, DUMMY_TEXT.find(' ').unwrap() + 1);
+        b.alt(Key::Char('d')).unwrap();
+        assert_eq!(&b.text[b.index..], &DUMMY_TEXT[idx_next_space..]);
+        b.alt(Key::Backspace).unwrap();
+        assert_eq!(b.index, DUMMY_TEXT.find(' ').unwrap());
+    }
+
+    #[test]
+    fn test_read_only_head_and_tail_are_kept_out_of_editing() {
+        let mut b = Buffer::from("example.com");
+        b.set_read_only("https://", "/");
+        b.home().unwrap();
+        assert_eq!(b.cursor, (0, 8));
+        b.backspace().unwrap();
+        b.end().unwrap();
+        b.del().unwrap();
+        assert_eq!(b.cursor, (0, 19));
+        assert_eq!(b.end_position(), (0, 20));
+        assert_eq!(b.to_string(), "example.com");
+        b.include_read_only = true;
+        assert_eq!(b.to_string(), "https://example.com/");
+    }
+
+    #[test]
+    fn test_end_then_backspace_on_multibyte_text() {
+        let mut b = Buffer::from("café noir");
+        b.end().unwrap();
+        assert_eq!(b.index, "café noir".len());
+        assert_eq!(b.cursor, (0, 9));
+        b.backspace().unwrap();
+        assert_eq!(b.to_string(), "café noi");
+        b.home().unwrap();
+        b.right().unwrap();
+        b.right().unwrap();
+        b.right().unwrap();
+        b.right().unwrap();
+        assert_eq!(b.cursor, (0, 4));
+        b.backspace().unwrap();
+        assert_eq!(b.to_string(), "caf noi");
+        b.end().unwrap();
+        assert_eq!(b.cursor, (0, 7));
+    }
+
+    #[test]
+    fn test_keymap_overrides_default_binding() {
+        let mut b = init_modifying_buffer();
+        assert_eq!(b.default_action(&Key::Char('x')), Some(Action::Insert('x')));
+        b.bind(Key::Char('\x17'), Action::WordBackspace);
+        b.bind(Key::Home, Action::KillToEnd);
+        assert_eq!(b.default_action(&Key::Home), Some(Action::Home));
+        assert_eq!(b.action(&Key::Home), Some(Action::KillToEnd));
+        assert_eq!(b.action(&Key::Char('\x17')), Some(Action::WordBackspace));
+        b.perform(Action::KillToEnd, &Key::Home).unwrap();
+        assert_eq!(b.to_string(), &DUMMY_TEXT[..DUMMY_INDEX]);
+        b.perform(Action::KillToStart, &Key::Unknown).unwrap();
+        assert_eq!(b.to_string(), "");
+    }
+
+    #[test]
+    fn test_submit_and_terminate_finish_with_the_key() {
+        let mut b = init_modifying_buffer();
+        assert_eq!(
+            b.perform(Action::Submit, &Key::Tab).unwrap(),
+            Some(Key::Tab)
+        );
+        assert_eq!(b.perform(Action::Left, &Key::ArrowLeft).unwrap(), None);
+        b.terminate_on_up_down = true;
+        assert_eq!(b.default_action(&Key::ArrowUp), Some(Action::Terminate));
+    }
+
+    #[test]
+    fn test_vi_normal_mode_moves_and_deletes() {
+        let mut b = Buffer::from("kabukiza ginza");
+        b.editing_mode = EditingMode::Vi;
+        b.index = b.text.len();
+        for key in [Key::Escape, Key::Char('b'), Key::Char('x'), Key::Char('0')] {
+            let action = b.default_action(&key).unwrap();
+            b.perform(action, &key).unwrap();
+        }
+        assert_eq!(b.to_string(), "kabukiza inza");
+        assert_eq!(b.index, 0);
+        for key in [Key::Char('A'), Key::Char('x')] {
+            let action = b.default_action(&key).unwrap();
+            b.perform(action, &key).unwrap();
+        }
+        assert_eq!(b.to_string(), "kabukiza inzax");
+        assert!(!b.vi_normal);
+    }
+
     #[test]
     fn test_left_key_after_characters_results_cursor_shift() {
         let mut b = init_modifying_buffer();
@@ -623,6 +2099,454 @@ mod tests {
         assert_eq!(b.index, idx_init);
     }
 
+    #[test]
+    fn test_long_input_keeps_cursor_on_wrapped_rows() {
+        let mut b = Buffer::new();
+        let width = b.width();
+        for _ in 0..width * 2 + 3 {
+            b.char('w').unwrap();
+        }
+        assert_eq!(b.cursor, (2, 3));
+        b.home().unwrap();
+        assert_eq!(b.cursor, (0, 0));
+        b.end().unwrap();
+        b.backspace().unwrap();
+        assert_eq!(b.cursor, (2, 2));
+    }
+
+    #[test]
+    fn test_wide_char_input_shifts_cursor_by_display_width() {
+        let mut b = Buffer::new();
+        b.char('日').unwrap();
+        b.char('本').unwrap();
+        assert_eq!(b.index, "日本".len());
+        assert_eq!(b.cursor, (0, 4));
+        b.left().unwrap();
+        assert_eq!(b.index, "日".len());
+        assert_eq!(b.cursor, (0, 2));
+    }
+
+    #[test]
+    fn test_newline_moves_cursor_to_next_row() {
+        let mut b = Buffer::new();
+        b.set_prefix("> ".to_string());
+        b.insert_str("abc").unwrap();
+        b.newline_on_enter = true;
+        b.char('\n').unwrap();
+        b.char('d').unwrap();
+        assert_eq!(b.text, "abc\nd");
+        assert_eq!(b.cursor, (1, 1));
+        b.home().unwrap();
+        assert_eq!(b.cursor, (0, 2));
+    }
+
+    #[test]
+    fn test_enter_inserts_newline_with_default_submit_key() {
+        let mut b = Buffer::new();
+        assert_eq!(b.action(&Key::Enter), Some(Action::Submit));
+        b.newline_on_enter = true;
+        assert_eq!(b.action(&Key::Enter), Some(Action::InsertNewline));
+        b.submit_key = Key::Tab;
+        assert_eq!(b.action(&Key::Tab), Some(Action::Submit));
+    }
+
+    #[test]
+    fn test_filled_row_before_newline_takes_a_row() {
+        let mut b = Buffer::new();
+        let width = b.width();
+        b.text = "w".repeat(width) + "\nw";
+        assert_eq!(b.position(width), (1, 0));
+        assert_eq!(b.position(b.text.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_tab_advances_cursor_to_next_tab_stop() {
+        let mut b = Buffer::from("a\tb\t");
+        b.set_prefix("> ".to_string());
+        assert_eq!(b.position(2), (0, 8));
+        assert_eq!(b.position(4), (0, 16));
+        b.tab_width = 4;
+        assert_eq!(b.position(2), (0, 4));
+        assert_eq!(b.rendered_from(0), "a b   ");
+    }
+
+    #[test]
+    fn test_tab_stops_at_row_end() {
+        let width = Buffer::new().width();
+        let mut b = Buffer::from(&format!("{}\tb", "a".repeat(width - 2)));
+        b.index = b.text.len();
+        assert_eq!(b.raw_position(width - 1), (0, width));
+        assert_eq!(b.position(width), (1, 1));
+        assert_eq!(b.rendered_from(width - 2), "  b");
+    }
+
+    #[test]
+    fn test_horizontal_scroll_keeps_cursor_in_view() {
+        let mut b = Buffer::from(DUMMY_TEXT);
+        b.set_prefix("> ".to_string());
+        b.horizontal_scroll = true;
+        let (head, shown, cursor, end) = b.window();
+        assert_eq!((head, cursor), (0, 2));
+        assert_eq!(shown, DUMMY_TEXT);
+        assert_eq!(end, 2 + DUMMY_TEXT.len());
+
+        b.text = DUMMY_TEXT.repeat(2);
+        b.index = b.text.len();
+        let (head, shown, cursor, end) = b.window();
+        assert!(head > 0);
+        assert!(shown.starts_with('…'));
+        assert_eq!(cursor, end);
+        // the columns for the right marker and the last one are left blank
+        assert_eq!(end, b.width() - 3);
+
+        b.index = 0;
+        let (head, shown, cursor, _) = b.window();
+        assert_eq!((head, cursor), (0, 2));
+        assert!(shown.ends_with('…'));
+    }
+
+    #[test]
+    fn test_cursor_column_counts_prefix_and_wide_chars() {
+        let mut b = Buffer::from("日本語");
+        b.set_prefix("\x1b[1m>\x1b[0m ".to_string());
+        assert_eq!(b.cursor_column(), 2);
+        b.index = "日本".len();
+        assert_eq!(b.cursor_column(), 6);
+    }
+
+    #[test]
+    fn test_clear_keeps_capacity_and_prefix() {
+        let mut b = init_modifying_buffer();
+        b.set_prefix("> ".to_string());
+        let capacity = b.text.capacity();
+        b.clear();
+        assert_eq!(b.index, 0);
+        assert_eq!(b.text, "");
+        assert_eq!(b.text.capacity(), capacity);
+        assert_eq!(b.prefix, "> ");
+    }
+
+    #[test]
+    fn test_insert_str_at_cursor() {
+        let mut b = init_with_word();
+        b.index = 2;
+        b.insert_str("日本\r\n").unwrap();
+        assert_eq!(b.text, "ka日本bukiza");
+        assert_eq!(b.index, "ka日本".len());
+        b.double_line_response = true;
+        b.insert_str("\r\n").unwrap();
+        assert_eq!(b.text, "ka日本\nbukiza");
+    }
+
+    #[test]
+    fn test_enter_appends_newline_at_end_for_double_line_response() {
+        let mut b = init_with_word();
+        b.double_line_response = true;
+        b.index = 2;
+        assert_eq!(b.enter().unwrap(), Key::Enter);
+        assert_eq!(b.text, "kabukiza\n");
+        assert_eq!(b.index, b.text.len());
+    }
+
+    #[test]
+    fn test_debug_shows_fields() {
+        let mut b = init_with_word();
+        b.set_prefix("> ".to_string());
+        let s = format!("{:?}", b);
+        assert!(s.starts_with("Buffer { debug: false,"));
+        assert!(s.contains("prefix: \"> \""));
+        assert!(s.ends_with("index: 0, text: \"kabukiza\", .. }"));
+    }
+
+    #[test]
+    fn test_buffers_with_same_text_and_index_are_equal() {
+        let mut b = init_with_word();
+        let mut expected = Buffer::from("kabukiza");
+        expected.trim = true;
+        assert_eq!(b, expected);
+        b.end().unwrap();
+        assert_ne!(b, expected);
+    }
+
+    #[test]
+    fn test_restore_returns_to_snapshot() {
+        let mut b = init_modifying_buffer();
+        b.set_prefix("> ".to_string());
+        let state = b.snapshot();
+        b.set_text("kabukiza").unwrap();
+        b.set_prefix("$ ".to_string());
+        b.restore(state.clone()).unwrap();
+        assert_eq!(b.snapshot(), state);
+        assert_eq!(state.text(), DUMMY_TEXT);
+        assert_eq!(b.cursor, b.position(b.index));
+    }
+
+    #[test]
+    fn test_set_text_replaces_text_and_puts_cursor_at_end() {
+        let mut b = init_modifying_buffer();
+        b.drawn = b.position(b.text.len());
+        b.set_text("kabukiza").unwrap();
+        assert_eq!(b.to_string(), "kabukiza");
+        assert_eq!(b.index, 8);
+        assert_eq!(b.cursor, (0, 8));
+        assert_eq!(b.drawn, (0, 8));
+    }
+
+    #[test]
+    fn test_insert_str_drops_control_characters() {
+        let mut b = Buffer::new();
+        b.insert_str("ab\x1b[31mc\r\nd\x07e\tf ü").unwrap();
+        assert_eq!(b.to_string(), "ab[31mcde\tf ü");
+        assert_eq!(b.default_action(&Key::Char('\x1b')), None);
+        assert_eq!(b.default_action(&Key::Char('\x17')), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_drain_input_without_tty_results_no_keys() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("drain-input");
+        let b = Buffer::with_term(term);
+        assert_eq!(b.drain_input().unwrap(), vec![]);
+        output(&path);
+    }
+
+    #[test]
+    fn test_rprompt_is_hidden_when_text_reaches_it() {
+        let mut b = init_with_word();
+        let width = b.width();
+        b.set_rprompt("[insert]".to_string());
+        assert_eq!(b.rprompt_column((0, 8)), Some(width - 9));
+        assert_eq!(b.rprompt_column((0, width - 9)), None);
+        assert_eq!(b.rprompt_column((1, 0)), None);
+        b.set_rprompt("r".repeat(width));
+        assert_eq!(b.rprompt_column((0, 0)), None);
+        assert_eq!(b.to_string(), "kabukiza");
+    }
+
+    #[test]
+    fn test_buffer_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Buffer>();
+    }
+
+    #[test]
+    fn test_on_change_is_called_for_each_edit() {
+        let texts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut b = init_modifying_buffer();
+        let t = texts.clone();
+        b.set_on_change(move |text| t.lock().unwrap().push(text.to_string()));
+        b.char('x').unwrap();
+        b.backspace().unwrap();
+        b.left().unwrap();
+        b.word_delete().unwrap();
+        b.home().unwrap();
+        b.backspace().unwrap();
+        let texts = texts.lock().unwrap();
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts[1], DUMMY_TEXT);
+        assert_eq!(texts[2], b.text);
+    }
+
+    #[test]
+    fn test_empty_text_is_rejected_unless_allowed() {
+        let mut b = Buffer::new();
+        assert!(b.allow_empty);
+        assert!(b.accepts().unwrap());
+        b.allow_empty = false;
+        assert!(!b.accepts().unwrap());
+        b.text = "   ".to_string();
+        assert!(!b.accepts().unwrap());
+        // the message is counted in the drawn area to be cleared by the next edit
+        assert_eq!(b.drawn, (0, 3 + EMPTY_INPUT_MESSAGE.len() + 1));
+        b.text = " a ".to_string();
+        assert!(b.accepts().unwrap());
+    }
+
+    #[test]
+    fn test_trim_text_removes_surrounding_whitespace() {
+        let mut b = Buffer::from("  okachimachi koshigaya \n ");
+        let capacity = b.text.capacity();
+        b.trim_text();
+        assert_eq!(b.to_string(), "okachimachi koshigaya");
+        assert_eq!(b.index, b.text.len());
+        assert_eq!(b.text.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_read_line_results_interrupted_on_cancel() {
+        let mut b = Buffer::from("kabukiza");
+        b.set_cancel(Arc::new(AtomicBool::new(true)));
+        let e = b.read_line().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::Interrupted);
+        assert_eq!(b.to_string(), "kabukiza");
+    }
+
+    #[test]
+    fn test_word_count_skips_repeated_separators() {
+        assert_eq!(init_modifying_buffer().word_count(), 5);
+        assert_eq!(Buffer::from("  kabukiza   ginza ").word_count(), 2);
+        assert_eq!(Buffer::new().word_count(), 0);
+    }
+
+    #[test]
+    fn test_line_count_counts_newlines() {
+        assert_eq!(Buffer::new().line_count(), 1);
+        assert_eq!(Buffer::from("kabukiza\nginza\n").line_count(), 3);
+    }
+
+    #[test]
+    fn test_readline_error_from_io_error_classifies_kind() {
+        let e = ReadlineError::from(crate::poll::cancelled());
+        assert!(matches!(e, ReadlineError::Cancelled));
+        let e = ReadlineError::from(io::Error::from(io::ErrorKind::Interrupted));
+        assert!(matches!(e, ReadlineError::Interrupted));
+        let e = ReadlineError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert!(matches!(e, ReadlineError::Eof));
+        let e = ReadlineError::from(io::Error::other("broken"));
+        assert_eq!(e.to_string(), "broken");
+    }
+
+    #[test]
+    fn test_hint_row_is_dimmed_and_fitted() {
+        let mut b = Buffer::new();
+        b.set_hint("h".repeat(b.width() + 5));
+        let row = b.hint_row();
+        assert!(row.starts_with("\r\n\x1b[2m"));
+        assert_eq!(measure_text_width(&row), b.width() - 1);
+    }
+
+    #[test]
+    fn test_on_key_gets_buffer_to_edit() {
+        let mut b = Buffer::from("abc");
+        assert_eq!(b.intercept(&Key::Tab), KeyAction::Passthrough);
+        b.set_on_key(|key, buf| match key {
+            Key::Char('\x15') => {
+                buf.text = buf.text.to_uppercase();
+                KeyAction::Handled
+            }
+            Key::Tab => KeyAction::Terminate,
+            _ => KeyAction::Passthrough,
+        });
+        assert_eq!(b.intercept(&Key::Char('\x15')), KeyAction::Handled);
+        assert_eq!(b.text, "ABC");
+        assert_eq!(b.intercept(&Key::Tab), KeyAction::Terminate);
+        assert_eq!(b.intercept(&Key::Char('x')), KeyAction::Passthrough);
+    }
+
+    #[test]
+    fn test_on_key_can_replace_itself() {
+        let mut b = Buffer::from("abc");
+        let mut count = 0;
+        b.set_on_key(move |_, buf| {
+            count += 1;
+            if count == 2 {
+                buf.set_on_key(|_, _| KeyAction::Terminate);
+            }
+            KeyAction::Handled
+        });
+        let mut c = b.clone();
+        assert_eq!(c.intercept(&Key::Tab), KeyAction::Passthrough);
+        assert_eq!(b.intercept(&Key::Tab), KeyAction::Handled);
+        assert_eq!(b.intercept(&Key::Tab), KeyAction::Handled);
+        assert_eq!(b.intercept(&Key::Tab), KeyAction::Terminate);
+    }
+
+    #[test]
+    fn test_clone_drops_on_key_and_shares_on_change() {
+        let changes = Arc::new(std::sync::Mutex::new(0));
+        let mut b = Buffer::from("abc");
+        let count = changes.clone();
+        b.set_on_change(move |_| *count.lock().unwrap() += 1);
+        b.set_on_key(|_, _| KeyAction::Terminate);
+        let mut c = b.clone();
+        assert_eq!(b.intercept(&Key::Tab), KeyAction::Terminate);
+        assert_eq!(c.intercept(&Key::Tab), KeyAction::Passthrough);
+        c.changed();
+        assert_eq!(*changes.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_debug_logs_key_to_sink() {
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut b = Buffer::from("a");
+        b.index = 1;
+        let sink = lines.clone();
+        b.set_debug_sink(move |line| sink.lock().unwrap().push(line.to_string()));
+        b.log_key(&Key::Char('x'), true, None);
+        assert!(lines.lock().unwrap().is_empty());
+        b.debug();
+        b.log_key(&Key::Char('a'), true, Some(Action::Insert('a')));
+        b.log_key(&Key::PageUp, false, None);
+        assert_eq!(
+            lines.lock().unwrap().as_slice(),
+            [
+                "key=Char('a') handled action=Some(Insert('a')) index=1 text=\"a\"",
+                "key=PageUp ignored action=None index=1 text=\"a\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emacs_control_keys_move_the_cursor() {
+        let b = Buffer::new();
+        #[cfg(not(unix))]
+        assert_eq!(b.action(&Key::Char('\x01')), Some(Action::Home));
+        #[cfg(not(unix))]
+        assert_eq!(b.action(&Key::Char('\x05')), Some(Action::End));
+        assert_eq!(b.action(&Key::Char('\x02')), Some(Action::Left));
+        assert_eq!(b.action(&Key::Char('\x06')), Some(Action::Right));
+        let mut b = init_modifying_buffer();
+        b.perform(Action::Home, &Key::Home).unwrap();
+        assert_eq!(b.index, 0);
+        b.perform(Action::Right, &Key::Char('\x06')).unwrap();
+        assert_eq!(b.index, 1);
+    }
+
+    #[test]
+    fn test_terminators_take_precedence_over_bindings() {
+        let mut b = Buffer::new();
+        assert_eq!(b.action(&Key::Tab), Some(Action::Insert('\t')));
+        b.set_terminators(vec![Key::Tab, Key::Escape]);
+        assert_eq!(b.action(&Key::Tab), Some(Action::Terminate));
+        assert_eq!(b.action(&Key::Escape), Some(Action::Terminate));
+        assert_eq!(b.action(&Key::Enter), Some(Action::Submit));
+    }
+
+    #[test]
+    fn test_escape_as_cancel_overrides_meta_and_vi() {
+        let mut b = Buffer::new();
+        assert_eq!(b.action(&Key::Escape), Some(Action::Meta));
+        b.escape_as_cancel = true;
+        assert_eq!(b.action(&Key::Escape), Some(Action::Cancel));
+        b.editing_mode = EditingMode::Vi;
+        assert_eq!(b.action(&Key::Escape), Some(Action::Cancel));
+        let e = b.perform(Action::Cancel, &Key::Escape).unwrap_err();
+        assert!(matches!(ReadlineError::from(e), ReadlineError::Cancelled));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ctrl_d_on_empty_text_results_eof() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("ctrl-d");
+        let mut b = Buffer::with_term(term);
+        assert_eq!(b.action(&Key::Char('\x04')), Some(Action::Eof));
+        let e = b.perform(Action::Eof, &Key::Char('\x04')).unwrap_err();
+        assert!(matches!(ReadlineError::from(e), ReadlineError::Eof));
+        b.text = "a".to_string();
+        assert_eq!(b.action(&Key::Char('\x04')), None);
+        drop(b);
+        output(&path);
+    }
+
+    #[test]
+    fn test_try_read_line_results_cancelled() {
+        let mut b = Buffer::new();
+        b.set_cancel(Arc::new(AtomicBool::new(true)));
+        assert!(matches!(b.try_read_line(), Err(ReadlineError::Cancelled)));
+    }
+
     #[test]
     fn test_set_prefix() {
         let mut b = init_with_word();
@@ -631,4 +2555,19 @@ mod tests {
         b.set_prefix(data.to_string());
         assert_eq!(b.prefix.len(), data.len());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restore_cursor_only_once() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("restore-cursor");
+        let mut buf = Buffer::with_term(term);
+        buf.cursor_hidden = true;
+        buf.showing_cursor = true;
+        buf.restore_cursor().unwrap();
+        assert!(!buf.showing_cursor);
+        buf.restore_cursor().unwrap();
+        drop(buf);
+        assert_eq!(output(&path), "\x1b[?25l");
+    }
 }
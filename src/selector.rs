@@ -3,14 +3,81 @@
 
 use std::io;
 use std::io::Write;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+#[cfg(unix)]
+use std::time::Instant;
 
-use chrono::{DateTime, Days, Duration, Local, Months};
-use console::{Key, Term};
+use crate::feedback::Feedback;
+use crate::key::read_plain_key_or_resize;
+use crate::poll::{is_cancelled_error, read_key_timeout};
+use crate::readline::Buffer;
+use chrono::{
+    DateTime, Datelike, Days, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone,
+    Timelike, Weekday,
+};
+use console::{measure_text_width, truncate_str, Key, Style, Term};
+
+/// Common interface of the interactive selectors, which redraw a frame and handle a key in turn
+/// until the selection is confirmed or cancelled.
+///
+/// ```rust,no_run
+/// use ttyui::selector::{DateSelector, Selector, WordSelector};
+///
+/// fn run<S: Selector>(mut s: S) -> S::Output {
+///     s.select().unwrap()
+/// }
+/// let date = run(DateSelector::new());
+/// let animal = run(WordSelector::new("animal", &["Elephant", "Horse"], Default::default()).unwrap());
+/// println!("{} {}", date, animal);
+/// ```
+///
+pub trait Selector {
+    /// Value of the confirmed selection.
+    type Output;
+
+    /// Terminal to draw on and read keys from.
+    fn term(&self) -> &Term;
+
+    /// Flag to cancel the selection from another thread, if any.
+    fn cancel_flag(&self) -> Option<&AtomicBool> {
+        None
+    }
+
+    /// Draw the current frame.
+    fn render(&self) -> io::Result<()>;
+
+    /// Handle a key, and return the output when the selection is confirmed.
+    ///
+    /// A cancelled selection results an error.
+    fn handle_key(&mut self, key: Key) -> io::Result<Option<Self::Output>>;
+
+    /// Run the selection interactively, redrawing on each key and on a resize.
+    ///
+    /// A set cancel flag results io::ErrorKind::Interrupted.
+    fn select(&mut self) -> io::Result<Self::Output> {
+        run_selection(self)
+    }
+}
+
+/// Loop of Selector::select(), for the selectors which run it inside their own setup.
+///
+fn run_selection<S: Selector + ?Sized>(s: &mut S) -> io::Result<S::Output> {
+    loop {
+        s.render()?;
+        if let Some(key) = read_plain_key_or_resize(s.term(), s.cancel_flag())? {
+            if let Some(output) = s.handle_key(key)? {
+                return Ok(output);
+            }
+        }
+    }
+}
 
 /// DateTimeField represents selector field for date and time.
 ///
-#[derive(Clone, Eq, PartialEq, Debug)]
-enum DateTimeField {
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum DateTimeField {
     Day,
     Month,
     Year,
@@ -22,7 +89,29 @@ enum DateTimeField {
 /// The ring buffer implementation for date and time, especially for the order of fields.
 ///
 impl DateTimeField {
-    fn switch_prev(&mut self) -> Self {
+    /// All fields in the navigation order.
+    ///
+    /// ```rust
+    /// use ttyui::selector::DateTimeField;
+    /// let fields = DateTimeField::all_fields();
+    /// assert_eq!(fields[0], DateTimeField::Year);
+    /// assert_eq!(fields[0].next(), fields[1]);
+    /// ```
+    ///
+    pub fn all_fields() -> [Self; 6] {
+        [
+            Self::Year,
+            Self::Month,
+            Self::Day,
+            Self::Hour,
+            Self::Minute,
+            Self::Second,
+        ]
+    }
+
+    /// Return the previous field in the ring.
+    ///
+    pub fn prev(self) -> Self {
         match self {
             Self::Year => Self::Second,
             Self::Month => Self::Year,
@@ -32,7 +121,10 @@ impl DateTimeField {
             Self::Second => Self::Minute,
         }
     }
-    fn switch_next(&mut self) -> Self {
+
+    /// Return the next field in the ring.
+    ///
+    pub fn next(self) -> Self {
         match self {
             Self::Year => Self::Month,
             Self::Month => Self::Day,
@@ -44,24 +136,48 @@ impl DateTimeField {
     }
 }
 
+/// Assignment of the arrow keys in DateSelector.
+///
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NavigationScheme {
+    /// Up/Down adjust the value and Left/Right switch the field
+    #[default]
+    UpDownAdjusts,
+    /// Left/Right adjust the value and Up/Down switch the field
+    LeftRightAdjusts,
+}
+
 const DEFAULT_DATE_NAME: &str = "due date";
+/// Green check mark in front of the summary line left on the selection.
+pub const SUMMARY_SYMBOL: &str = "\x1b[32m✓\x1b[0m";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Columns of the calendar grid, which is 7 days of 2 digits separated by a space.
+const CALENDAR_WIDTH: usize = 20;
 
 /// The interactive selector interface for date and time.
 ///
 /// By default, `DateSelector::new()` returns a selector for **date**, NOT FOR **date** and **time**.
-/// If you want to select date and time with a selector, set DateSelector.has_time true
-/// before calling select() method.
+/// If you want to select date and time with a selector, call DateSelector.set_has_time(true)
+/// before calling select() method. The `/` key prompts the whole date to be typed,
+/// such as `2024-12-31` (or `2024-12-31 23:59:59` with time), and a date in the same format
+/// pasted into the selector is applied as well.
+/// Home and End jump to the first and the last field.
 ///
 /// An instance for the date selection must be mutable and the selected date (or datetime) can be
 /// extracted within different formats:
 ///
+/// With DateSelector.calendar set, the date is selected on a month calendar grid instead,
+/// where the arrow keys move by a day or a week and PageUp/PageDown move by a month.
+///
 /// * DateSelector.get_date() -> `chrono::DateTime<Local>`
 /// * DateSelector.to_string() -> String
+/// * DateSelector.format_date("%+") -> String in a strftime format
 ///
-/// ```rust
+/// ```rust,no_run
 /// use ttyui::selector::DateSelector;
 /// let mut d = DateSelector::new();
-/// d.has_time = true;
+/// d.set_has_time(true);
 /// println!("selected: {}", d.select().unwrap().to_string());
 /// ```
 ///
@@ -69,8 +185,40 @@ const DEFAULT_DATE_NAME: &str = "due date";
 pub struct DateSelector {
     /// date name for the selection
     pub name: String,
+    /// style for the date name (no style by default)
+    pub name_style: Style,
     /// whether the selector supports time selection or not
-    pub has_time: bool,
+    has_time: bool,
+    /// keys to confirm the selection (Enter only by default)
+    pub confirm_keys: Vec<Key>,
+    /// years which the selection can move within (the whole range of chrono by default)
+    pub year_range: RangeInclusive<i32>,
+    /// whether the day wraps within the month on up/down, such as 31 to 1, instead of changing the month
+    pub day_wraps_in_month: bool,
+    /// earliest date to be selected, which is itself excluded with Bound::Excluded (unbounded by default)
+    pub min: Bound<DateTime<Local>>,
+    /// latest date to be selected, which is itself excluded with Bound::Excluded (unbounded by default)
+    pub max: Bound<DateTime<Local>>,
+    /// days of the week which cannot be selected, such as weekends (none by default)
+    pub disabled_weekdays: Vec<Weekday>,
+    /// assignment of the arrow keys to the value and the field (Up/Down adjust the value by default)
+    pub navigation_scheme: NavigationScheme,
+    /// whether the date is selected on a month calendar grid, instead of the fields
+    pub calendar: bool,
+    /// first day of the week in the columns of the calendar grid (Monday by default)
+    pub week_start: Weekday,
+    /// feedback emitted when the selection is confirmed (none by default)
+    pub feedback_on_submit: Option<Feedback>,
+    /// whether a summary line `✓ name: date` is left on the selection, instead of the cleared screen
+    pub summary: bool,
+    /// symbol in front of the summary line (SUMMARY_SYMBOL by default)
+    pub summary_symbol: String,
+    /// whether the final `name: value` line is left on the selection, with the rest of the frame cleared
+    pub inline_echo: bool,
+    /// flag to cancel the selection from another thread
+    cancel: Option<Arc<AtomicBool>>,
+    /// error message shown under the date, until the next key
+    message: Option<String>,
     /// active (on-cursor) field for the selection
     active_field: DateTimeField,
     /// selected date (datetime)
@@ -79,15 +227,37 @@ pub struct DateSelector {
     term: Term,
 }
 
+impl Default for DateSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DateSelector {
     /// Generate selector instance with current date/time
     ///
     pub fn new() -> Self {
         Self {
             name: DEFAULT_DATE_NAME.to_string(),
+            name_style: Style::new(),
             active_field: DateTimeField::Day,
             date: Local::now(),
             has_time: false,
+            confirm_keys: vec![Key::Enter],
+            year_range: NaiveDate::MIN.year()..=NaiveDate::MAX.year(),
+            day_wraps_in_month: false,
+            min: Bound::Unbounded,
+            max: Bound::Unbounded,
+            disabled_weekdays: Vec::new(),
+            navigation_scheme: NavigationScheme::UpDownAdjusts,
+            calendar: false,
+            week_start: Weekday::Mon,
+            feedback_on_submit: None,
+            summary: false,
+            summary_symbol: SUMMARY_SYMBOL.to_string(),
+            inline_echo: false,
+            cancel: None,
+            message: None,
             term: Term::stdout(),
         }
     }
@@ -97,19 +267,193 @@ impl DateSelector {
     pub fn from(date: DateTime<Local>) -> Self {
         Self {
             name: DEFAULT_DATE_NAME.to_string(),
+            name_style: Style::new(),
             active_field: DateTimeField::Day,
             date,
             has_time: false,
+            confirm_keys: vec![Key::Enter],
+            year_range: NaiveDate::MIN.year()..=NaiveDate::MAX.year(),
+            day_wraps_in_month: false,
+            min: Bound::Unbounded,
+            max: Bound::Unbounded,
+            disabled_weekdays: Vec::new(),
+            navigation_scheme: NavigationScheme::UpDownAdjusts,
+            calendar: false,
+            week_start: Weekday::Mon,
+            feedback_on_submit: None,
+            summary: false,
+            summary_symbol: SUMMARY_SYMBOL.to_string(),
+            inline_echo: false,
+            cancel: None,
+            message: None,
             term: Term::stdout(),
         }
     }
 
+    /// Generate selector instance with current date/time, which draws on the terminal.
+    ///
+    /// Pass a clone of the Term the application holds, to share the terminal state with it.
+    ///
+    pub fn with_term(term: Term) -> Self {
+        let mut d = Self::new();
+        d.term = term;
+        d
+    }
+
     /// Set date, not interactively.
     ///
     pub fn set_date(&mut self, date: DateTime<Local>) {
         self.date = date;
     }
 
+    /// Prompt the date typed as a whole with readline, which is applied if it is valid.
+    ///
+    /// An empty input keeps the date, and an invalid one leaves an error message for the next frame.
+    /// Escape or Ctrl-D on the empty input also keeps the date and returns to the selector.
+    ///
+    fn type_date(&mut self) -> io::Result<()> {
+        let mut buf = Buffer::with_term(self.term.clone());
+        buf.escape_as_cancel = true;
+        self.type_date_with(buf)
+    }
+
+    /// Body of type_date, reading the date with the buffer.
+    ///
+    fn type_date_with(&mut self, mut buf: Buffer) -> io::Result<()> {
+        self.term.clear_screen()?;
+        buf.set_prefix(self.label());
+        buf.trim = true;
+        match buf.read_line() {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof || is_cancelled_error(&e) => {
+                return Ok(())
+            }
+            Err(e) => return Err(e),
+        }
+        if buf.to_string().is_empty() {
+            return Ok(());
+        }
+        self.apply_typed(&buf.to_string());
+        Ok(())
+    }
+
+    /// Take the characters pasted after `first`, which arrive at once unlike typed ones,
+    /// and apply them as a date.
+    ///
+    /// The paste ends at the first key which is not a character, such as a trailing newline.
+    /// A single typed digit is ignored as before.
+    ///
+    fn paste(&mut self, first: char) -> io::Result<()> {
+        let mut text = first.to_string();
+        while let Some(key) = read_key_timeout(&self.term, std::time::Duration::ZERO)? {
+            match key {
+                Key::Char(c) => text.push(c),
+                _ => break,
+            }
+        }
+        if text.len() > 1 {
+            self.apply_typed(text.trim());
+        }
+        Ok(())
+    }
+
+    /// Apply the date in the format of the selector, or leave an error message for the next frame.
+    ///
+    fn apply_typed(&mut self, text: &str) {
+        match self.parse(text) {
+            Some(date) => {
+                self.date = date;
+                self.normalize();
+            }
+            None => {
+                let expected = match self.has_time {
+                    true => "YYYY-MM-DD hh:mm:ss",
+                    false => "YYYY-MM-DD",
+                };
+                self.message = Some(format!("invalid date, expected {}", expected));
+            }
+        }
+    }
+
+    /// Parse a date in the format of the selector, keeping the time for the date only selector.
+    ///
+    fn parse(&self, s: &str) -> Option<DateTime<Local>> {
+        let date = match self.has_time {
+            true => NaiveDateTime::parse_from_str(s, DATETIME_FORMAT).ok()?,
+            false => NaiveDate::parse_from_str(s, DATE_FORMAT)
+                .ok()?
+                .and_time(self.date.time()),
+        };
+        Local.from_local_datetime(&date).single()
+    }
+
+    /// Set a flag to cancel the selection from another thread.
+    ///
+    /// The flag is checked while waiting for a key, and the selection results
+    /// io::ErrorKind::Interrupted when it is set.
+    ///
+    pub fn set_cancel(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel = Some(flag);
+    }
+
+    /// Return the active field and its current value.
+    ///
+    /// The year is counted in its era, as chrono's `Datelike::year_ce` does.
+    ///
+    pub fn active_field_value(&self) -> (DateTimeField, u32) {
+        let value = match self.active_field {
+            DateTimeField::Year => self.date.year_ce().1,
+            DateTimeField::Month => self.date.month(),
+            DateTimeField::Day => self.date.day(),
+            DateTimeField::Hour => self.date.hour(),
+            DateTimeField::Minute => self.date.minute(),
+            DateTimeField::Second => self.date.second(),
+        };
+        (self.active_field, value)
+    }
+
+    /// Whether the selector supports time selection or not.
+    ///
+    pub fn has_time(&self) -> bool {
+        self.has_time
+    }
+
+    /// Switch the time selection on or off.
+    ///
+    /// When the time is disabled on a time field, the active field moves to Day.
+    ///
+    pub fn set_has_time(&mut self, has_time: bool) {
+        self.has_time = has_time;
+        if self.is_out_of_field() {
+            self.active_field = DateTimeField::Day;
+        }
+    }
+
+    /// Set the active field to start the selection on.
+    ///
+    /// A time field for the instance without time support results io::ErrorKind::InvalidInput,
+    /// leaving the active field unchanged.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::selector::{DateSelector, DateTimeField};
+    /// let mut d = DateSelector::new();
+    /// d.set_active_field(DateTimeField::Year).unwrap();
+    /// println!("selected: {}", d.select().unwrap().to_string());
+    /// ```
+    ///
+    pub fn set_active_field(&mut self, field: DateTimeField) -> io::Result<()> {
+        let prev = self.active_field;
+        self.active_field = field;
+        if self.is_out_of_field() {
+            self.active_field = prev;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is out of the date fields", field),
+            ));
+        }
+        Ok(())
+    }
+
     /// This method detects whether the instance supports the field under the cursor.
     ///
     /// If the instance has no time range support, (but supports date only), it returns
@@ -118,17 +462,17 @@ impl DateSelector {
     fn is_out_of_field(&self) -> bool {
         match &self.has_time {
             true => false,
-            false => match self.active_field {
-                DateTimeField::Year | DateTimeField::Month | DateTimeField::Day => false,
-                _ => true,
-            },
+            false => !matches!(
+                self.active_field,
+                DateTimeField::Year | DateTimeField::Month | DateTimeField::Day
+            ),
         }
     }
 
     /// Move left for ring-bufferish selection field.
     ///
     pub fn left(&mut self) -> io::Result<()> {
-        self.active_field = self.active_field.switch_prev();
+        self.active_field = self.active_field.prev();
         if self.is_out_of_field() {
             self.active_field = DateTimeField::Day;
         }
@@ -139,7 +483,7 @@ impl DateSelector {
     /// Move right for ring-bufferish selection field.
     ///
     pub fn right(&mut self) -> io::Result<()> {
-        self.active_field = self.active_field.switch_next();
+        self.active_field = self.active_field.next();
         if self.is_out_of_field() {
             self.active_field = DateTimeField::Year;
         }
@@ -150,279 +494,2277 @@ impl DateSelector {
     /// Adjust cursor position before selection, after date characters written.
     ///
     fn adjust(&self) -> io::Result<()> {
-        let msg_len = self.to_string().len();
-        self.term.move_cursor_left(msg_len)?;
-        match &self.active_field {
-            DateTimeField::Year => self.term.move_cursor_right(3)?,
-            DateTimeField::Month => self.term.move_cursor_right(6)?,
-            DateTimeField::Day => self.term.move_cursor_right(9)?,
-            DateTimeField::Hour => self.term.move_cursor_right(12)?,
-            DateTimeField::Minute => self.term.move_cursor_right(15)?,
-            DateTimeField::Second => self.term.move_cursor_right(18)?,
-        };
+        self.term.write_str("\r")?;
+        self.term.move_cursor_right(self.cursor_column())?;
         Ok(())
     }
 
+    /// Label in front of the date, which is the styled name followed by `: `.
+    ///
+    fn label(&self) -> String {
+        format!("{}: ", self.name_style.apply_to(&self.name))
+    }
+
+    /// Column of the cursor for the active field, measured with the visible width of the label.
+    ///
+    fn cursor_column(&self) -> usize {
+        measure_text_width(&self.label()) + self.field_span().1 - 1
+    }
+
+    /// Byte range of the active field in the formatted date.
+    ///
+    /// The year can be wider than 4 digits with a sign, such as `+12345` or `-0044`.
+    ///
+    fn field_span(&self) -> (usize, usize) {
+        let year = self.date.format("%Y").to_string().len();
+        let start = match &self.active_field {
+            DateTimeField::Year => return (0, year),
+            DateTimeField::Month => year + 1,
+            DateTimeField::Day => year + 4,
+            DateTimeField::Hour => year + 7,
+            DateTimeField::Minute => year + 10,
+            DateTimeField::Second => year + 13,
+        };
+        (start, start + 2)
+    }
+
+    /// Write the `name: value` line of the current frame to `w`, with the active field in reverse video.
+    ///
+    /// It does not touch the terminal, which is useful to test the rendering.
+    ///
+    /// ```rust
+    /// use ttyui::selector::DateSelector;
+    /// use chrono::{Local, TimeZone};
+    ///
+    /// let d = DateSelector::from(Local.with_ymd_and_hms(2024, 12, 31, 0, 0, 0).unwrap());
+    /// let mut out = Vec::new();
+    /// d.render_to(&mut out).unwrap();
+    /// assert_eq!(out, b"due date: 2024-12-\x1b[7m31\x1b[0m");
+    /// ```
+    ///
+    pub fn render_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let value = self.to_string();
+        let (start, end) = self.field_span();
+        write!(
+            w,
+            "{}{}\x1b[7m{}\x1b[0m{}",
+            self.label(),
+            &value[..start],
+            &value[start..end],
+            &value[end..]
+        )
+    }
+
     /// Increment a value under the cursor.
     ///
+    /// The date is kept if the result is out of `year_range` or the range of chrono.
+    ///
     pub fn up(&mut self) -> io::Result<()> {
-        match &self.active_field {
-            DateTimeField::Year => {
-                self.date = self.date.checked_add_months(Months::new(12)).unwrap();
-            }
-            DateTimeField::Month => {
-                self.date = self.date.checked_add_months(Months::new(1)).unwrap();
-            }
-            DateTimeField::Day => {
-                self.date = self.date.checked_add_days(Days::new(1)).unwrap();
-            }
-            _ => {
-                match &self.has_time {
-                    true => match &self.active_field {
-                        DateTimeField::Hour => {
-                            self.date = self.date + Duration::hours(1);
-                        }
-                        DateTimeField::Minute => {
-                            self.date = self.date + Duration::minutes(1);
-                        }
-                        DateTimeField::Second => {
-                            self.date = self.date + Duration::seconds(1);
-                        }
-                        _ => {}
-                    },
-                    false => {
-                        self.active_field = DateTimeField::Day;
-                    }
-                };
-            }
+        let date = match &self.active_field {
+            DateTimeField::Year => self.date.checked_add_months(Months::new(12)),
+            DateTimeField::Month => self.date.checked_add_months(Months::new(1)),
+            DateTimeField::Day if self.day_wraps_in_month => step_day_in_month(self.date, true),
+            DateTimeField::Day => self.date.checked_add_days(Days::new(1)),
+            _ => match &self.has_time {
+                true => match &self.active_field {
+                    DateTimeField::Hour => self.date.checked_add_signed(Duration::hours(1)),
+                    DateTimeField::Minute => self.date.checked_add_signed(Duration::minutes(1)),
+                    DateTimeField::Second => self.date.checked_add_signed(Duration::seconds(1)),
+                    _ => None,
+                },
+                false => {
+                    self.active_field = DateTimeField::Day;
+                    None
+                }
+            },
         };
+        self.move_date(date, true, self.wraps_day());
         Ok(())
     }
 
     /// Decrement a value under the cursor.
     ///
+    /// The date is kept if the result is out of `year_range` or the range of chrono.
+    ///
     pub fn down(&mut self) -> io::Result<()> {
-        match &self.active_field {
-            DateTimeField::Year => {
-                self.date = self.date.checked_sub_months(Months::new(12)).unwrap();
-            }
-            DateTimeField::Month => {
-                self.date = self.date.checked_sub_months(Months::new(1)).unwrap();
-            }
-            DateTimeField::Day => {
-                self.date = self.date.checked_sub_days(Days::new(1)).unwrap();
-            }
-            _ => {
-                match self.has_time {
-                    true => match &self.active_field {
-                        DateTimeField::Hour => {
-                            self.date = self.date - Duration::hours(1);
-                        }
-                        DateTimeField::Minute => {
-                            self.date = self.date - Duration::minutes(1);
-                        }
-                        DateTimeField::Second => {
-                            self.date = self.date - Duration::seconds(1);
-                        }
-                        _ => {}
-                    },
-                    false => {
-                        self.active_field = DateTimeField::Day;
-                    }
-                };
-            }
+        let date = match &self.active_field {
+            DateTimeField::Year => self.date.checked_sub_months(Months::new(12)),
+            DateTimeField::Month => self.date.checked_sub_months(Months::new(1)),
+            DateTimeField::Day if self.day_wraps_in_month => step_day_in_month(self.date, false),
+            DateTimeField::Day => self.date.checked_sub_days(Days::new(1)),
+            _ => match &self.has_time {
+                true => match &self.active_field {
+                    DateTimeField::Hour => self.date.checked_sub_signed(Duration::hours(1)),
+                    DateTimeField::Minute => self.date.checked_sub_signed(Duration::minutes(1)),
+                    DateTimeField::Second => self.date.checked_sub_signed(Duration::seconds(1)),
+                    _ => None,
+                },
+                false => {
+                    self.active_field = DateTimeField::Day;
+                    None
+                }
+            },
         };
+        self.move_date(date, false, self.wraps_day());
         Ok(())
     }
 
-    /// Return selected date.
+    /// Add years to the date, saturating at `year_range`, `min` and `max`.
     ///
-    pub fn get_date(&self) -> DateTime<Local> {
-        self.date.clone()
-    }
-
-    /// Select date interactively.
+    /// The active field is kept, and the day is clamped to the month as in up() and down().
     ///
     /// ```rust
+    /// use std::ops::Bound;
+    ///
+    /// use chrono::{Local, TimeZone};
     /// use ttyui::selector::DateSelector;
-    /// let mut d = DateSelector::new();
-    /// d.has_time = true;
-    /// println!("selected: {}", d.select().unwrap().to_string());
+    ///
+    /// let mut d = DateSelector::from(Local.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap());
+    /// d.add_years(1);
+    /// assert_eq!(d.to_string(), "2025-02-28");
+    /// d.max = Bound::Included(Local.with_ymd_and_hms(2025, 6, 30, 0, 0, 0).unwrap());
+    /// d.add_months(12);
+    /// assert_eq!(d.to_string(), "2025-06-30");
     /// ```
     ///
-    pub fn select(&mut self) -> io::Result<&mut Self> {
-        loop {
-            self.term.clear_screen()?;
-            write!(&self.term, "{}: {}", self.name, self.to_string())?;
-            self.adjust()?;
-
-            match self.term.read_key()? {
-                Key::ArrowLeft => {
-                    self.left()?;
-                    self.adjust()?;
-                }
-                Key::ArrowRight => {
-                    self.right()?;
-                    self.adjust()?;
-                }
-                Key::ArrowUp => {
-                    self.up()?;
-                }
-                Key::ArrowDown => {
-                    self.down()?;
-                }
-                Key::Enter => break,
-                _ => {}
-            };
-        }
-        self.term.clear_screen()?;
-        Ok(self)
+    pub fn add_years(&mut self, years: i32) {
+        // i32::MAX months are still out of the range of chrono, which is saturated at the bound
+        self.add_months(years.saturating_mul(12));
     }
-}
 
-impl ToString for DateSelector {
-    fn to_string(&self) -> String {
-        match self.has_time {
-            true => format!("{}", self.date.format("%Y-%m-%d %H:%M:%S")),
-            false => format!("{}", self.date.format("%Y-%m-%d")),
-        }
+    /// Add months to the date, saturating at `year_range`, `min` and `max`.
+    ///
+    pub fn add_months(&mut self, months: i32) {
+        let n = Months::new(months.unsigned_abs());
+        let date = match months < 0 {
+            true => self.date.checked_sub_months(n),
+            false => self.date.checked_add_months(n),
+        };
+        self.saturate(date, months >= 0);
     }
-}
 
-/// Select date with default conditions
-///
-pub fn select_date(initial_date: DateTime<Local>) -> io::Result<DateTime<Local>> {
-    // println!("input {:?}", initial_date);
-    Ok(DateSelector::from(initial_date).select()?.get_date())
-}
+    /// Add days to the date, saturating at `year_range`, `min` and `max`.
+    ///
+    pub fn add_days(&mut self, days: i64) {
+        let date = Duration::try_days(days).and_then(|d| self.date.checked_add_signed(d));
+        self.saturate(date, days >= 0);
+    }
 
-/// Select date with time range
-///
-pub fn select_datetime(initial_date: DateTime<Local>) -> io::Result<DateTime<Local>> {
-    // println!("input {:?}", initial_date);
-    let mut t = DateSelector::from(initial_date);
-    t.has_time = true;
-    Ok(t.select()?.get_date())
-}
+    /// Add hours to the date, saturating at `year_range`, `min` and `max`.
+    ///
+    pub fn add_hours(&mut self, hours: i64) {
+        let date = Duration::try_hours(hours).and_then(|d| self.date.checked_add_signed(d));
+        self.saturate(date, hours >= 0);
+    }
 
-/// Select date with custom date title
-///
-pub fn select_date_with_title(
-    initial_date: DateTime<Local>,
-    title: &str,
-) -> io::Result<DateTime<Local>> {
-    let mut t = DateSelector::from(initial_date);
-    t.has_time = false;
-    t.name = title.to_string();
-    Ok(t.select()?.get_date())
-}
+    /// Add minutes to the date, saturating at `year_range`, `min` and `max`.
+    ///
+    pub fn add_minutes(&mut self, minutes: i64) {
+        let date = Duration::try_minutes(minutes).and_then(|d| self.date.checked_add_signed(d));
+        self.saturate(date, minutes >= 0);
+    }
 
-/// Select date with time range and custom date title
-///
-pub fn select_datetime_with_title(
-    initial_date: DateTime<Local>,
-    title: &str,
-) -> io::Result<DateTime<Local>> {
-    // println!("input {:?}", initial_date);
-    let mut t = DateSelector::from(initial_date);
-    t.has_time = true;
-    t.name = title.to_string();
-    Ok(t.select()?.get_date())
-}
+    /// Add seconds to the date, saturating at `year_range`, `min` and `max`.
+    ///
+    pub fn add_seconds(&mut self, seconds: i64) {
+        let date = Duration::try_seconds(seconds).and_then(|d| self.date.checked_add_signed(d));
+        self.saturate(date, seconds >= 0);
+    }
 
-/// A traditional selector to tell user something and requests `y` or `n`.
-///
-pub fn ask_yes_no(question_msg: &str) -> io::Result<bool> {
-    let mut term = Term::stdout();
-    let mut msg = format!("{}: ", question_msg);
+    /// Set the date added by add_*(), or the bound in the direction if it overflows chrono,
+    /// and clamp it into `year_range`, `min` and `max`.
+    ///
+    fn saturate(&mut self, date: Option<DateTime<Local>>, forward: bool) {
+        let bound = if forward { self.max } else { self.min };
+        match (date, bound) {
+            (Some(date), _) => self.date = date,
+            (None, Bound::Included(date) | Bound::Excluded(date)) => self.date = date,
+            (None, Bound::Unbounded) => {}
+        }
+        let year = self
+            .date
+            .year()
+            .clamp(*self.year_range.start(), *self.year_range.end());
+        if year != self.date.year() {
+            let date = NaiveDate::from_ymd_opt(year, self.date.month(), 1)
+                .and_then(|d| d.with_day(self.date.day().min(month_length(d))))
+                .and_then(|d| {
+                    Local
+                        .from_local_datetime(&d.and_time(self.date.time()))
+                        .single()
+                });
+            if let Some(date) = date {
+                self.date = date;
+            }
+        }
+        self.normalize();
+    }
 
-    write!(term, "{}", msg)?;
-    loop {
-        match term.read_key().unwrap() {
-            Key::Char('Y') | Key::Char('y') => {
-                write!(term, "y\n")?;
-                return Ok(true);
-            }
-            Key::Char('N') | Key::Char('n') => {
-                write!(term, "n\n")?;
-                return Ok(false);
-            }
-            _ => {
-                term.clear_chars(msg.len())?;
-                term.move_cursor_left(msg.len())?;
-                msg = "Answer with y or n: ".to_string();
-                write!(term, "{}", msg)?;
-                continue;
+    /// Move to the date if it is within `year_range`.
+    ///
+    /// A date on a disabled weekday is skipped to the next allowed one in the direction of the move,
+    /// within the month if `wraps` is set.
+    ///
+    fn move_date(&mut self, date: Option<DateTime<Local>>, forward: bool, wraps: bool) {
+        let Some(mut date) = date else {
+            return;
+        };
+        for _ in 0..7 {
+            if !self.year_range.contains(&date.year()) || !self.in_bounds(&date) {
+                return;
+            }
+            if !self.is_disabled(&date) {
+                self.date = date;
+                return;
             }
+            let next = match wraps {
+                true => step_day_in_month(date, forward),
+                false => step_day(date, forward),
+            };
+            date = match next {
+                Some(date) => date,
+                None => return,
+            };
         }
     }
-}
 
-/// Item selection interface for a slice of descriptions.
-///
-/// This method returns a selected line with new String literal, or io::Error::Other for `Q` or escape key pressed.
-///
-/// ```rust
-/// use ttyui::selector::select_word_from_words;
-///
-/// let animals = [
-///     "Elephant",
-///     "Horse",
-///     "Whale",
-///     "Tiger",
-///     "Panda",
-/// ];
-/// println!("selected: {}",select_word_from_words("your favorite animal", &animals).unwrap());
-/// ```
+    /// Whether up() and down() on the active field wrap the day within the month.
+    ///
+    fn wraps_day(&self) -> bool {
+        self.day_wraps_in_month && self.active_field == DateTimeField::Day
+    }
 
-pub fn select_word_from_words(description: &str, items: &[&str]) -> io::Result<String> {
-    let term = Term::stdout();
-    term.clear_line()?;
-    let mut seq = 0;
-    let word_count = items.len();
-    let mut table: Vec<&str> = Vec::with_capacity(word_count);
-    table.push("\x1b[32m*\x1b[0m");
-    for _ in 0..word_count - 1 {
-        table.push(" ");
+    /// Whether the date is within `min` and `max`, with their inclusivity.
+    ///
+    fn in_bounds(&self, date: &DateTime<Local>) -> bool {
+        (self.min, self.max).contains(date)
     }
-    loop {
-        term.clear_screen()?;
-        term.write_line(description)?;
-        for i in 0..word_count {
-            write!(&term, "{} {}\n", table[i], items[i])?;
-        }
-        seq = match term.read_key().unwrap() {
-            Key::ArrowUp | Key::Char('k') => {
-                if seq == 0 {
-                    word_count - 1
-                } else {
-                    seq - 1
-                }
-            }
-            Key::ArrowDown | Key::Char('j') => {
-                if seq == word_count - 1 {
-                    0
-                } else {
-                    seq + 1
-                }
-            }
-            Key::Char('q') | Key::Char('Q') | Key::Escape => {
+
+    /// Smallest step of the date, by which an excluded bound is stepped over in the clamping.
+    ///
+    fn finest_step(&self) -> Duration {
+        match self.has_time {
+            true => Duration::seconds(1),
+            false => Duration::days(1),
+        }
+    }
+
+    /// Whether the date falls on a disabled weekday.
+    ///
+    fn is_disabled(&self, date: &DateTime<Local>) -> bool {
+        self.disabled_weekdays.contains(&date.weekday())
+    }
+
+    /// Clamp the date into `min` and `max`, and move it off a disabled weekday to the nearest
+    /// allowed date within them, the later one for a tie.
+    ///
+    /// This is called at the start of the selection, so that it starts from a date which can be returned.
+    ///
+    /// ```rust
+    /// use std::ops::Bound;
+    ///
+    /// use chrono::{Local, TimeZone, Weekday};
+    /// use ttyui::selector::DateSelector;
+    ///
+    /// // Saturday
+    /// let mut d = DateSelector::from(Local.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap());
+    /// d.max = Bound::Included(Local.with_ymd_and_hms(2024, 5, 31, 0, 0, 0).unwrap());
+    /// d.disabled_weekdays = vec![Weekday::Fri];
+    /// d.normalize();
+    /// assert_eq!(d.to_string(), "2024-05-30");
+    /// ```
+    ///
+    pub fn normalize(&mut self) {
+        let step = self.finest_step();
+        let min = match self.min {
+            Bound::Included(min) => Some(min),
+            Bound::Excluded(min) => min.checked_add_signed(step),
+            Bound::Unbounded => None,
+        };
+        if let Some(min) = min.filter(|min| self.date < *min) {
+            self.date = min;
+        }
+        let max = match self.max {
+            Bound::Included(max) => Some(max),
+            Bound::Excluded(max) => max.checked_sub_signed(step),
+            Bound::Unbounded => None,
+        };
+        if let Some(max) = max.filter(|max| self.date > *max) {
+            self.date = max;
+        }
+        if !self.is_disabled(&self.date) {
+            return;
+        }
+        let (mut later, mut earlier) = (Some(self.date), Some(self.date));
+        for _ in 0..7 {
+            later = later.and_then(|d| step_day(d, true));
+            earlier = earlier.and_then(|d| step_day(d, false));
+            for date in [later, earlier].into_iter().flatten() {
+                if self.in_bounds(&date) && !self.is_disabled(&date) {
+                    self.date = date;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Return selected date.
+    ///
+    pub fn get_date(&self) -> DateTime<Local> {
+        self.date
+    }
+
+    /// Borrow the selected date, such as for a custom render loop.
+    ///
+    pub fn date(&self) -> &DateTime<Local> {
+        &self.date
+    }
+
+    /// Borrow the selected date to edit it in place.
+    ///
+    /// The edited date is not checked against the bounds and the disabled weekdays,
+    /// for which normalize() can be called afterwards. The next frame draws the edited date.
+    ///
+    /// ```rust
+    /// use chrono::{Local, TimeZone, Timelike};
+    /// use ttyui::selector::DateSelector;
+    ///
+    /// let mut d = DateSelector::from(Local.with_ymd_and_hms(2024, 5, 31, 12, 30, 0).unwrap());
+    /// d.set_has_time(true);
+    /// if let Some(date) = d.date().with_minute(0) {
+    ///     *d.date_mut() = date;
+    /// }
+    /// assert_eq!(d.to_string(), "2024-05-31 12:00:00");
+    /// ```
+    ///
+    pub fn date_mut(&mut self) -> &mut DateTime<Local> {
+        &mut self.date
+    }
+
+    /// Return selected date formatted with a strftime pattern, or an empty string for an invalid pattern.
+    ///
+    /// ```rust
+    /// use chrono::{Local, TimeZone};
+    /// use ttyui::selector::DateSelector;
+    ///
+    /// let d = DateSelector::from(Local.with_ymd_and_hms(2024, 5, 31, 12, 0, 0).unwrap());
+    /// assert_eq!(d.format_date("%d/%m/%Y %H:%M"), "31/05/2024 12:00");
+    /// ```
+    ///
+    pub fn format_date(&self, fmt: &str) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        match write!(out, "{}", self.date.format(fmt)) {
+            Ok(()) => out,
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Select date interactively.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::selector::DateSelector;
+    /// let mut d = DateSelector::new();
+    /// d.set_has_time(true);
+    /// println!("selected: {}", d.select().unwrap().to_string());
+    /// ```
+    ///
+    pub fn select(&mut self) -> io::Result<&mut Self> {
+        self.select_key()?;
+        Ok(self)
+    }
+
+    /// Select date interactively and return the key which confirmed the selection.
+    ///
+    /// Any key in `confirm_keys` finishes the selection. Confirm keys are checked before
+    /// the navigation keys, so the arrow keys should not be registered for them.
+    ///
+    /// ```rust,no_run
+    /// use console::Key;
+    /// use ttyui::selector::DateSelector;
+    /// let mut d = DateSelector::new();
+    /// d.confirm_keys.push(Key::Char('c'));
+    /// let k = d.select_key().unwrap();
+    /// println!("selected: {} (confirmed with {:?})", d.to_string(), k);
+    /// ```
+    ///
+    pub fn select_key(&mut self) -> io::Result<Key> {
+        self.normalize();
+        loop {
+            self.render()?;
+            if let Some(key) = read_plain_key_or_resize(&self.term, self.cancel.as_deref())? {
+                if self.handle_key(key.clone())?.is_some() {
+                    return Ok(key);
+                }
+            }
+        }
+    }
+
+    /// Select date interactively, where Escape cancels the selection and results None.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::selector::DateSelector;
+    /// match DateSelector::new().try_select().unwrap() {
+    ///     Some(date) => println!("selected: {}", date.format("%Y-%m-%d")),
+    ///     None => println!("cancelled"),
+    /// }
+    /// ```
+    ///
+    pub fn try_select(&mut self) -> io::Result<Option<DateTime<Local>>> {
+        self.normalize();
+        loop {
+            self.render()?;
+            match read_plain_key_or_resize(&self.term, self.cancel.as_deref())? {
+                Some(Key::Escape) => {
+                    self.term.clear_screen()?;
+                    return Ok(None);
+                }
+                Some(key) => {
+                    if let Some(date) = self.handle_key(key)? {
+                        return Ok(Some(date));
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Month calendar of the date, with the day highlighted, and the column and row of the day.
+    ///
+    /// Weeks start on `week_start`.
+    ///
+    fn calendar_grid(&self) -> (String, (usize, usize)) {
+        let date = self.date.date_naive();
+        let first = date.with_day(1).unwrap();
+        let days = self.days_in_month() as usize;
+        let offset = first.weekday().days_since(self.week_start) as usize;
+        let header: Vec<String> = (0..7)
+            .scan(self.week_start, |day, _| {
+                let name = day.to_string()[..2].to_string();
+                *day = day.succ();
+                Some(name)
+            })
+            .collect();
+
+        let mut out = format!(
+            "{}{}\n{}\n",
+            self.label(),
+            date.format("%Y-%m"),
+            header.join(" ")
+        );
+        let mut cursor = (0, 0);
+        for cell in 0..offset + days {
+            let day = (cell + 1).saturating_sub(offset);
+            if cell < offset {
+                out += "  ";
+            } else if day == date.day() as usize {
+                out += &format!("\x1b[7m{:>2}\x1b[0m", day);
+                cursor = (3 * (cell % 7) + 1, 2 + cell / 7);
+            } else {
+                out += &format!("{:>2}", day);
+            }
+            out += if cell % 7 == 6 { "\n" } else { " " };
+        }
+        (out, cursor)
+    }
+
+    /// Number of days in the month of the date.
+    ///
+    fn days_in_month(&self) -> u32 {
+        month_length(self.date.date_naive())
+    }
+
+    /// Move the date on the calendar grid with a key.
+    ///
+    fn calendar_move(&mut self, key: Key) {
+        let date = match key {
+            Key::ArrowLeft => self.date.checked_sub_days(Days::new(1)),
+            Key::ArrowRight => self.date.checked_add_days(Days::new(1)),
+            Key::ArrowUp => self.date.checked_sub_days(Days::new(7)),
+            Key::ArrowDown => self.date.checked_add_days(Days::new(7)),
+            Key::PageUp => self.date.checked_sub_months(Months::new(1)),
+            Key::PageDown => self.date.checked_add_months(Months::new(1)),
+            _ => None,
+        };
+        let forward = matches!(key, Key::ArrowRight | Key::ArrowDown | Key::PageDown);
+        self.move_date(date, forward, false);
+    }
+
+    /// Whether the calendar grid is drawn, which needs the terminal wide enough.
+    ///
+    fn shows_calendar(&self) -> bool {
+        self.calendar && self.term.size().1 as usize >= CALENDAR_WIDTH
+    }
+
+    /// Select many dates interactively, which are toggled with the space key.
+    ///
+    /// The dates are navigated as select() does, and a toggled date is marked with `*`.
+    /// Toggling it again removes it from the selection. The confirm keys return
+    /// the toggled dates in the ascending order.
+    ///
+    /// ```rust,no_run
+    /// use ttyui::selector::DateSelector;
+    /// let mut d = DateSelector::new();
+    /// d.name = "vacation days".to_string();
+    /// for date in d.select_many().unwrap() {
+    ///     println!("{}", date.format("%Y-%m-%d"));
+    /// }
+    /// ```
+    ///
+    pub fn select_many(&mut self) -> io::Result<Vec<DateTime<Local>>> {
+        self.normalize();
+        let mut picked = Vec::new();
+        loop {
+            self.render_many(&picked)?;
+            let Some(key) = read_plain_key_or_resize(&self.term, self.cancel.as_deref())? else {
+                continue;
+            };
+            if key == Key::Char(' ') {
+                self.toggle(&mut picked);
+            } else if self.handle_key(key)?.is_some() {
+                picked.sort();
+                return Ok(picked);
+            }
+        }
+    }
+
+    /// Draw the date with the toggle marker, and the number of the toggled dates under it.
+    ///
+    fn render_many(&self, picked: &[DateTime<Local>]) -> io::Result<()> {
+        let mark = if self.position_in(picked).is_some() {
+            " *"
+        } else {
+            ""
+        };
+        self.term.clear_screen()?;
+        write!(&self.term, "{}{}{}", self.label(), self, mark)?;
+        match &self.message {
+            Some(message) => write!(&self.term, "\n{}", message)?,
+            None => write!(&self.term, "\n{} selected", picked.len())?,
+        }
+        self.term.move_cursor_up(1)?;
+        self.adjust()
+    }
+
+    /// Add the current date to the toggled dates, or remove it if it is toggled already.
+    ///
+    fn toggle(&self, picked: &mut Vec<DateTime<Local>>) {
+        match self.position_in(picked) {
+            Some(i) => {
+                picked.remove(i);
+            }
+            None => picked.push(self.date),
+        }
+    }
+
+    /// Index of the current date in the dates, compared by the shown value.
+    ///
+    fn position_in(&self, dates: &[DateTime<Local>]) -> Option<usize> {
+        let format = match self.has_time {
+            true => DATETIME_FORMAT,
+            false => DATE_FORMAT,
+        };
+        let shown = self.date.format(format).to_string();
+        dates
+            .iter()
+            .position(|d| d.format(format).to_string() == shown)
+    }
+}
+
+/// The next or the previous day of the date.
+///
+fn step_day(date: DateTime<Local>, forward: bool) -> Option<DateTime<Local>> {
+    match forward {
+        true => date.checked_add_days(Days::new(1)),
+        false => date.checked_sub_days(Days::new(1)),
+    }
+}
+
+/// The next or the previous day of the date, wrapping within the month, such as 31 to 1.
+///
+fn step_day_in_month(date: DateTime<Local>, forward: bool) -> Option<DateTime<Local>> {
+    let days = month_length(date.date_naive());
+    match (forward, date.day()) {
+        (true, day) => date.with_day(day % days + 1),
+        (false, 1) => date.with_day(days),
+        (false, day) => date.with_day(day - 1),
+    }
+}
+
+impl Selector for DateSelector {
+    type Output = DateTime<Local>;
+
+    fn term(&self) -> &Term {
+        &self.term
+    }
+
+    fn cancel_flag(&self) -> Option<&AtomicBool> {
+        self.cancel.as_deref()
+    }
+
+    /// Run the selection from the date normalized into the bounds, as select_key() does.
+    fn select(&mut self) -> io::Result<DateTime<Local>> {
+        self.normalize();
+        run_selection(self)
+    }
+
+    fn render(&self) -> io::Result<()> {
+        self.term.clear_screen()?;
+        if self.shows_calendar() {
+            let (grid, (col, row)) = self.calendar_grid();
+            self.term.write_str(&grid)?;
+            return self.term.move_cursor_to(col, row);
+        }
+        write!(&self.term, "{}{}", self.label(), self)?;
+        if let Some(message) = &self.message {
+            // kept in a row, to move back to the date
+            let width = (self.term.size().1 as usize).saturating_sub(1);
+            if measure_text_width(message) > width {
+                write!(&self.term, "\n{}", truncate_str(message, width, "…"))?;
+            } else {
+                write!(&self.term, "\n{}", message)?;
+            }
+            self.term.move_cursor_up(1)?;
+        }
+        self.adjust()
+    }
+
+    fn handle_key(&mut self, key: Key) -> io::Result<Option<DateTime<Local>>> {
+        self.message = None;
+        if self.confirm_keys.contains(&key) {
+            // such as bounds changed during the selection, or no date between them
+            if !self.in_bounds(&self.date) {
+                self.message = Some("out of range".to_string());
+                return Ok(None);
+            }
+            // such as a date typed in, or weekdays disabled during the selection
+            if self.is_disabled(&self.date) {
+                self.message = Some(format!("{} is not available", self.date.weekday()));
+                return Ok(None);
+            }
+            if self.inline_echo {
+                // the frame is drawn from the top, where the field line is rewritten
+                self.term.move_cursor_to(0, 0)?;
+                writeln!(&self.term, "{}{}\x1b[0J", self.label(), self)?;
+            } else {
+                self.term.clear_screen()?;
+            }
+            if self.summary {
+                let name = self.name_style.apply_to(&self.name).to_string();
+                self.term
+                    .write_line(&summary_line(&self.summary_symbol, &name, &*self))?;
+            }
+            if let Some(feedback) = self.feedback_on_submit {
+                feedback.emit(&self.term)?;
+            }
+            return Ok(Some(self.date));
+        }
+        match key {
+            Key::Char('/') => self.type_date()?,
+            Key::Char(c) if c.is_ascii_digit() => self.paste(c)?,
+            key if self.shows_calendar() => self.calendar_move(key),
+            Key::Home => {
+                self.active_field = DateTimeField::Year;
+                self.adjust()?;
+            }
+            Key::End => {
+                self.active_field = DateTimeField::Second;
+                if self.is_out_of_field() {
+                    self.active_field = DateTimeField::Day;
+                }
+                self.adjust()?;
+            }
+            key => match (self.navigation_scheme, key) {
+                (NavigationScheme::UpDownAdjusts, Key::ArrowLeft) => self.left()?,
+                (NavigationScheme::UpDownAdjusts, Key::ArrowRight) => self.right()?,
+                (NavigationScheme::UpDownAdjusts, Key::ArrowUp) => self.up()?,
+                (NavigationScheme::UpDownAdjusts, Key::ArrowDown) => self.down()?,
+                (NavigationScheme::LeftRightAdjusts, Key::ArrowUp) => self.left()?,
+                (NavigationScheme::LeftRightAdjusts, Key::ArrowDown) => self.right()?,
+                (NavigationScheme::LeftRightAdjusts, Key::ArrowRight) => self.up()?,
+                (NavigationScheme::LeftRightAdjusts, Key::ArrowLeft) => self.down()?,
+                _ => {}
+            },
+        };
+        Ok(None)
+    }
+}
+
+impl std::fmt::Display for DateSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.has_time {
+            true => write!(f, "{}", self.date.format(DATETIME_FORMAT)),
+            false => write!(f, "{}", self.date.format(DATE_FORMAT)),
+        }
+    }
+}
+
+/// Number of days in the month of the date.
+///
+fn month_length(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap();
+    match first.checked_add_months(Months::new(1)) {
+        Some(next) => next.signed_duration_since(first).num_days() as u32,
+        // December of the last year of chrono
+        None => 31,
+    }
+}
+
+/// Select date with default conditions
+///
+pub fn select_date(initial_date: DateTime<Local>) -> io::Result<DateTime<Local>> {
+    // println!("input {:?}", initial_date);
+    Ok(DateSelector::from(initial_date).select()?.get_date())
+}
+
+/// Select date with time range
+///
+pub fn select_datetime(initial_date: DateTime<Local>) -> io::Result<DateTime<Local>> {
+    // println!("input {:?}", initial_date);
+    let mut t = DateSelector::from(initial_date);
+    t.set_has_time(true);
+    Ok(t.select()?.get_date())
+}
+
+/// Select date with default conditions, or None if it is cancelled with Escape
+///
+/// ```rust,no_run
+/// use chrono::Local;
+/// use ttyui::selector::try_select_date;
+///
+/// if let Some(date) = try_select_date(Local::now()).unwrap() {
+///     println!("due: {}", date.format("%Y-%m-%d"));
+/// }
+/// ```
+///
+pub fn try_select_date(initial_date: DateTime<Local>) -> io::Result<Option<DateTime<Local>>> {
+    DateSelector::from(initial_date).try_select()
+}
+
+/// Select date with time range, or None if it is cancelled with Escape
+///
+pub fn try_select_datetime(initial_date: DateTime<Local>) -> io::Result<Option<DateTime<Local>>> {
+    let mut t = DateSelector::from(initial_date);
+    t.set_has_time(true);
+    t.try_select()
+}
+
+/// Select date with custom date title
+///
+pub fn select_date_with_title(
+    initial_date: DateTime<Local>,
+    title: &str,
+) -> io::Result<DateTime<Local>> {
+    let mut t = DateSelector::from(initial_date);
+    t.set_has_time(false);
+    t.name = title.to_string();
+    Ok(t.select()?.get_date())
+}
+
+/// Select date with time range and custom date title
+///
+pub fn select_datetime_with_title(
+    initial_date: DateTime<Local>,
+    title: &str,
+) -> io::Result<DateTime<Local>> {
+    // println!("input {:?}", initial_date);
+    let mut t = DateSelector::from(initial_date);
+    t.set_has_time(true);
+    t.name = title.to_string();
+    Ok(t.select()?.get_date())
+}
+
+const DEFAULT_YES_NO_REPROMPT: &str = "Answer with y or n";
+
+/// Presentation of the y/n prompt.
+///
+/// The default shows `": "` after the question, and echoes `y` or `n` with a newline.
+///
+/// Set `newline` false to keep the cursor after the answer, or `clear_line` to overwrite
+/// the prompt line with the output of the caller, without a blank line left.
+///
+/// ```rust,no_run
+/// use ttyui::selector::{ask_yes_no_with_options, YesNoOptions};
+///
+/// let options = YesNoOptions {
+///     suffix: " [y/N] ".to_string(),
+///     yes_echo: "yes".to_string(),
+///     no_echo: "no".to_string(),
+///     ..Default::default()
+/// };
+/// ask_yes_no_with_options("overwrite?", &options).unwrap();
+/// ```
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YesNoOptions {
+    /// message which replaces the question when other keys are pressed
+    pub reprompt: String,
+    /// string shown after the question and the reprompt message
+    pub suffix: String,
+    /// string echoed for `y`
+    pub yes_echo: String,
+    /// string echoed for `n`
+    pub no_echo: String,
+    /// print a newline after the echoed answer
+    pub newline: bool,
+    /// clear the prompt line on answer, leaving the cursor at the head of it instead of echoing
+    pub clear_line: bool,
+}
+
+impl Default for YesNoOptions {
+    fn default() -> Self {
+        YesNoOptions {
+            reprompt: DEFAULT_YES_NO_REPROMPT.to_string(),
+            suffix: ": ".to_string(),
+            yes_echo: "y".to_string(),
+            no_echo: "n".to_string(),
+            newline: true,
+            clear_line: false,
+        }
+    }
+}
+
+/// A traditional selector to tell user something and requests `y` or `n`.
+///
+pub fn ask_yes_no(question_msg: &str) -> io::Result<bool> {
+    ask_yes_no_with_options(question_msg, &YesNoOptions::default())
+}
+
+/// A y/n selector with custom message, which replaces the question when other keys are pressed.
+///
+/// ```rust,no_run
+/// use ttyui::selector::ask_yes_no_with_reprompt;
+/// ask_yes_no_with_reprompt("続けますか？", "y か n で答えてください").unwrap();
+/// ```
+///
+pub fn ask_yes_no_with_reprompt(question_msg: &str, reprompt_msg: &str) -> io::Result<bool> {
+    let options = YesNoOptions {
+        reprompt: reprompt_msg.to_string(),
+        ..Default::default()
+    };
+    ask_yes_no_with_options(question_msg, &options)
+}
+
+/// A y/n selector with the suffix and the echoed answers given in the options.
+///
+pub fn ask_yes_no_with_options(question_msg: &str, options: &YesNoOptions) -> io::Result<bool> {
+    ask_answer(question_msg, options, |key| match key {
+        Key::Char('Y') | Key::Char('y') => Some((true, options.yes_echo.as_str())),
+        Key::Char('N') | Key::Char('n') => Some((false, options.no_echo.as_str())),
+        _ => None,
+    })
+}
+
+/// A y/n selector where Enter answers the default, which is echoed as `y` or `n` as if it were typed.
+///
+/// The default is hinted by the capital letter, as `[Y/n]` for yes, and the other keys reprompt.
+///
+/// ```rust,no_run
+/// use ttyui::selector::ask_yes_no_default;
+///
+/// if ask_yes_no_default("Delete the branch?", false).unwrap() {
+///     println!("deleting");
+/// }
+/// ```
+///
+pub fn ask_yes_no_default(question_msg: &str, default: bool) -> io::Result<bool> {
+    let options = YesNoOptions {
+        suffix: if default { " [Y/n]: " } else { " [y/N]: " }.to_string(),
+        ..Default::default()
+    };
+    ask_answer(question_msg, &options, |key| match key {
+        Key::Char('Y') | Key::Char('y') => Some((true, "y")),
+        Key::Char('N') | Key::Char('n') => Some((false, "n")),
+        Key::Enter if default => Some((true, "y")),
+        Key::Enter => Some((false, "n")),
+        _ => None,
+    })
+}
+
+/// A y/n selector which answers the default when no key is pressed within the timeout.
+///
+/// The seconds left are counted down after the question, and Enter answers the default at once.
+/// This is available only on unix, where the terminal can be polled for a key.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use ttyui::selector::ask_yes_no_timeout;
+///
+/// if ask_yes_no_timeout("Continue?", true, Duration::from_secs(10)).unwrap() {
+///     println!("continuing");
+/// }
+/// ```
+///
+#[cfg(unix)]
+pub fn ask_yes_no_timeout(
+    question_msg: &str,
+    default: bool,
+    timeout: std::time::Duration,
+) -> io::Result<bool> {
+    let term = Term::stdout();
+    let choices = if default { "Y/n" } else { "y/N" };
+    let deadline = Instant::now() + timeout;
+    let answer = loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        term.clear_line()?;
+        write!(&term, "{} [{}] {}s: ", question_msg, choices, secs)?;
+        if left.is_zero() {
+            break default;
+        }
+        // until the shown seconds change
+        let wait = left - std::time::Duration::from_secs(secs - 1);
+        match read_key_timeout(&term, wait)? {
+            Some(Key::Char('Y')) | Some(Key::Char('y')) => break true,
+            Some(Key::Char('N')) | Some(Key::Char('n')) => break false,
+            Some(Key::Enter) => break default,
+            _ => {}
+        }
+    };
+    writeln!(&term, "{}", if answer { 'y' } else { 'n' })?;
+    Ok(answer)
+}
+
+/// Answer for a question in a batch, which can apply to the rest of the questions.
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Yes,
+    No,
+    /// yes for this and the rest of the questions
+    YesToAll,
+    /// no for this and the rest of the questions
+    NoToAll,
+}
+
+/// A y/n selector for batch operations, which also accepts `a` for yes-to-all and `s` or `!` for no-to-all.
+///
+/// ```rust,no_run
+/// use ttyui::selector::{ask_yes_no_all, Answer};
+///
+/// let mut all = None;
+/// for file in ["a.txt", "b.txt", "c.txt"] {
+///     let answer = match all {
+///         Some(answer) => answer,
+///         None => ask_yes_no_all(&format!("remove {}? (y/n/a/s)", file)).unwrap(),
+///     };
+///     match answer {
+///         Answer::YesToAll | Answer::NoToAll => all = Some(answer),
+///         _ => {}
+///     }
+///     if matches!(answer, Answer::Yes | Answer::YesToAll) {
+///         println!("removing {}", file);
+///     }
+/// }
+/// ```
+///
+pub fn ask_yes_no_all(question_msg: &str) -> io::Result<Answer> {
+    let options = YesNoOptions {
+        reprompt: "Answer with y, n, a or s".to_string(),
+        ..Default::default()
+    };
+    ask_answer(question_msg, &options, |key| match key {
+        Key::Char('Y') | Key::Char('y') => Some((Answer::Yes, "y")),
+        Key::Char('N') | Key::Char('n') => Some((Answer::No, "n")),
+        Key::Char('A') | Key::Char('a') => Some((Answer::YesToAll, "a")),
+        Key::Char('S') | Key::Char('s') | Key::Char('!') => Some((Answer::NoToAll, "s")),
+        _ => None,
+    })
+}
+
+/// Ask y/n questions in order, each with the progress like `(2/5)`, and return the answers.
+///
+/// Escape aborts the batch, and the answers given so far are returned, which are fewer than the questions.
+///
+/// ```rust,no_run
+/// use ttyui::selector::ask_yes_no_batch;
+///
+/// let questions = ["install the binary?", "add it to PATH?", "install the manual?"];
+/// let answers = ask_yes_no_batch(&questions).unwrap();
+/// if answers.len() < questions.len() {
+///     println!("aborted");
+/// }
+/// ```
+///
+pub fn ask_yes_no_batch(questions: &[&str]) -> io::Result<Vec<bool>> {
+    let options = YesNoOptions::default();
+    let mut answers = Vec::with_capacity(questions.len());
+    for (i, question) in questions.iter().enumerate() {
+        let msg = format!("({}/{}) {}", i + 1, questions.len(), question);
+        let answer = ask_answer(&msg, &options, |key| match key {
+            Key::Char('Y') | Key::Char('y') => Some((Some(true), "y")),
+            Key::Char('N') | Key::Char('n') => Some((Some(false), "n")),
+            Key::Escape => Some((None, "")),
+            _ => None,
+        })?;
+        match answer {
+            Some(answer) => answers.push(answer),
+            None => break,
+        }
+    }
+    Ok(answers)
+}
+
+/// Ask a question until a key is mapped to an answer, which is echoed with the mapped string.
+///
+/// The question is replaced with the reprompt message when other keys are pressed.
+///
+fn ask_answer<'a, T>(
+    question_msg: &str,
+    options: &YesNoOptions,
+    answer: impl Fn(&Key) -> Option<(T, &'a str)>,
+) -> io::Result<T> {
+    let term = Term::stdout();
+    ask_answer_on(&term, question_msg, options, || term.read_key(), answer)
+}
+
+fn ask_answer_on<'a, T>(
+    term: &Term,
+    question_msg: &str,
+    options: &YesNoOptions,
+    mut read_key: impl FnMut() -> io::Result<Key>,
+    answer: impl Fn(&Key) -> Option<(T, &'a str)>,
+) -> io::Result<T> {
+    let mut msg = format!("{}{}", question_msg, options.suffix);
+
+    write!(&*term, "{}", msg)?;
+    loop {
+        match answer(&read_key()?) {
+            Some((value, _)) if options.clear_line => {
+                term.clear_line()?;
+                return Ok(value);
+            }
+            Some((value, echo)) => {
+                write!(&*term, "{}", echo)?;
+                if options.newline {
+                    writeln!(&*term)?;
+                }
+                return Ok(value);
+            }
+            None => {
+                term.clear_chars(measure_text_width(&msg))?;
+                msg = format!("{}{}", options.reprompt, options.suffix);
+                write!(&*term, "{}", msg)?;
+            }
+        }
+    }
+}
+
+/// Item selection interface for a slice of descriptions.
+///
+/// This method returns a selected line with new String literal, or io::Error::Other for `Q` or escape key pressed.
+/// An empty slice of items results io::ErrorKind::InvalidInput.
+///
+/// ```rust,no_run
+/// use ttyui::selector::select_word_from_words;
+///
+/// let animals = [
+///     "Elephant",
+///     "Horse",
+///     "Whale",
+///     "Tiger",
+///     "Panda",
+/// ];
+/// println!("selected: {}",select_word_from_words("your favorite animal", &animals).unwrap());
+/// ```
+///
+/// Owned strings can be passed as well.
+///
+/// ```rust,no_run
+/// use ttyui::selector::select_word_from_words;
+///
+/// let animals: Vec<String> = vec!["Elephant".to_string(), "Horse".to_string()];
+/// println!("selected: {}",select_word_from_words("your favorite animal", &animals).unwrap());
+/// ```
+///
+pub fn select_word_from_words<S: AsRef<str>>(description: &str, items: &[S]) -> io::Result<String> {
+    select_word_with_options(description, items, &ListOptions::default())
+}
+
+/// Result of the item selection, which tells a selected item from quitting.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SelectionOutcome {
+    /// an item is selected, with its index in the items
+    Selected { index: usize, value: String },
+    /// the selection is quit with a quit key
+    Quit,
+}
+
+impl SelectionOutcome {
+    /// Index of the selected item, or None for Quit.
+    ///
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            SelectionOutcome::Selected { index, .. } => Some(*index),
+            SelectionOutcome::Quit => None,
+        }
+    }
+
+    /// Selected item as it was when it was selected, or None for Quit.
+    ///
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            SelectionOutcome::Selected { value, .. } => Some(value),
+            SelectionOutcome::Quit => None,
+        }
+    }
+}
+
+/// Item at the index returned by a selector, or None if the index is out of the items.
+///
+/// ```rust
+/// use ttyui::selector::selected_value;
+///
+/// let animals = ["Elephant", "Horse"];
+/// assert_eq!(selected_value(&animals, 1), Some("Horse"));
+/// assert_eq!(selected_value(&animals, 2), None);
+/// ```
+///
+pub fn selected_value<S: AsRef<str>>(items: &[S], index: usize) -> Option<&str> {
+    items.get(index).map(|item| item.as_ref())
+}
+
+/// Item selection interface, which returns the selected index and value, or Quit without an error.
+///
+/// ```rust,no_run
+/// use ttyui::selector::{select_word_detailed, SelectionOutcome};
+///
+/// let animals = ["Elephant", "Horse", "Whale"];
+/// match select_word_detailed("your favorite animal", &animals).unwrap() {
+///     SelectionOutcome::Selected { index, value } => println!("{}: {}", index, value),
+///     SelectionOutcome::Quit => println!("no animal"),
+/// }
+/// ```
+///
+pub fn select_word_detailed<S: AsRef<str>>(
+    description: &str,
+    items: &[S],
+) -> io::Result<SelectionOutcome> {
+    ListSelector::new(description).select(items)
+}
+
+/// Builder of the item selection, which is configured once and selects from items.
+///
+/// ```rust,no_run
+/// use ttyui::selector::{ListSelector, SelectionOutcome, LIST_HELP};
+///
+/// let selector = ListSelector::new("your favorite animal")
+///     .filter(true)
+///     .numbering(1)
+///     .footer(LIST_HELP);
+/// match selector.select(&["Elephant", "Horse", "Whale"]).unwrap() {
+///     SelectionOutcome::Selected { index, value } => println!("{}: {}", index, value),
+///     SelectionOutcome::Quit => println!("no animal"),
+/// }
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct ListSelector {
+    description: String,
+    options: ListOptions,
+    term: Term,
+    /// whether the quit keys are given, rather than defaulted by the filter
+    quit_keys_set: bool,
+}
+
+impl ListSelector {
+    /// Generate a builder with the default options.
+    ///
+    pub fn new(description: &str) -> Self {
+        ListSelector {
+            description: description.to_string(),
+            options: ListOptions::default(),
+            term: Term::stdout(),
+            quit_keys_set: false,
+        }
+    }
+
+    /// Replace all the options at once.
+    ///
+    pub fn options(mut self, options: ListOptions) -> Self {
+        self.options = options;
+        self.quit_keys_set = true;
+        self
+    }
+
+    /// Filter the items with typed characters.
+    ///
+    /// Only Escape quits with the filter, as in select_word_with_filter(), unless quit_keys() is given.
+    ///
+    pub fn filter(mut self, filter: bool) -> Self {
+        self.options.filter = filter;
+        if !self.quit_keys_set {
+            self.options.quit_keys = match filter {
+                true => vec![Key::Escape],
+                false => ListOptions::default().quit_keys,
+            };
+        }
+        self
+    }
+
+    /// Match the typed query as a subsequence of the items, which are sorted by the match score.
+    ///
+    pub fn fuzzy(mut self, fuzzy: bool) -> Self {
+        self.options.fuzzy = fuzzy;
+        self
+    }
+
+    /// Require an item to be selected, ignoring the quit keys which are not typed into the query.
+    ///
+    pub fn required(mut self, required: bool) -> Self {
+        self.options.required = required;
+        self
+    }
+
+    /// Leave a summary line `✓ description: item` on the selection.
+    ///
+    pub fn summary(mut self, summary: bool) -> Self {
+        self.options.summary = summary;
+        self
+    }
+
+    /// Symbol in front of the summary line, SUMMARY_SYMBOL by default, or none with "".
+    ///
+    pub fn summary_symbol(mut self, symbol: &str) -> Self {
+        self.options.summary_symbol = symbol.to_string();
+        self
+    }
+
+    /// Keys to quit the selection.
+    ///
+    pub fn quit_keys(mut self, keys: Vec<Key>) -> Self {
+        self.options.quit_keys = keys;
+        self.quit_keys_set = true;
+        self
+    }
+
+    /// Feedback emitted when an item is selected.
+    ///
+    pub fn feedback_on_submit(mut self, feedback: Feedback) -> Self {
+        self.options.feedback_on_submit = Some(feedback);
+        self
+    }
+
+    /// Query to narrow the items down at the start, with the filter.
+    ///
+    pub fn initial_query(mut self, query: &str) -> Self {
+        self.options.initial_query = query.to_string();
+        self
+    }
+
+    /// Dimmed help line below the items.
+    ///
+    pub fn footer(mut self, footer: &str) -> Self {
+        self.options.footer = Some(footer.to_string());
+        self
+    }
+
+    /// Number the items from `first`.
+    ///
+    pub fn numbering(mut self, first: usize) -> Self {
+        self.options.numbering = Some(first);
+        self
+    }
+
+    /// Run the selection in the alternate screen, not to leave the menu in the scrollback.
+    ///
+    pub fn alt_screen(mut self, alt_screen: bool) -> Self {
+        self.options.alt_screen = alt_screen;
+        self
+    }
+
+    /// Highlight the selected item with the marker or in reverse video.
+    ///
+    pub fn highlight(mut self, highlight: HighlightStyle) -> Self {
+        self.options.highlight = highlight;
+        self
+    }
+
+    /// Terminal to draw on and read keys from.
+    ///
+    pub fn term(mut self, term: Term) -> Self {
+        self.term = term;
+        self
+    }
+
+    /// Select an item interactively, and return the selected index and value, or Quit.
+    ///
+    /// An empty slice of items results io::ErrorKind::InvalidInput.
+    ///
+    pub fn select<S: AsRef<str>>(&self, items: &[S]) -> io::Result<SelectionOutcome> {
+        let mut s = WordSelector::with_term(
+            &self.description,
+            items,
+            self.options.clone(),
+            self.term.clone(),
+        )?;
+        match s.select() {
+            Ok(value) => Ok(SelectionOutcome::Selected {
+                index: s.selected_index().unwrap_or_default(),
+                value,
+            }),
+            Err(e) if is_quit_error(&e) => Ok(SelectionOutcome::Quit),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Payload of the error for the item selection quit with a quit key.
+///
+#[derive(Debug)]
+struct Quit;
+
+impl std::fmt::Display for Quit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "quit")
+    }
+}
+
+impl std::error::Error for Quit {}
+
+fn is_quit_error(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<Quit>())
+}
+
+/// Item selection interface with type-to-filter.
+///
+/// Typed characters narrow the items down to those containing the query, case-insensitively,
+/// and the matched part of each item is highlighted. Backspace edits the query,
+/// arrow keys move the selection and Enter selects an item.
+/// Since the characters are taken for the query, only the escape key quits with io::Error::Other.
+///
+/// ```rust,no_run
+/// use ttyui::selector::select_word_with_filter;
+///
+/// let animals = ["Elephant", "Horse", "Whale", "Tiger", "Panda"];
+/// println!("selected: {}", select_word_with_filter("your favorite animal", &animals).unwrap());
+/// ```
+///
+pub fn select_word_with_filter<S: AsRef<str>>(
+    description: &str,
+    items: &[S],
+) -> io::Result<String> {
+    let options = ListOptions {
+        filter: true,
+        quit_keys: vec![Key::Escape],
+        ..Default::default()
+    };
+    select_word_with_options(description, items, &options)
+}
+
+/// Options for the item selection.
+///
+#[derive(Clone, Debug)]
+pub struct ListOptions {
+    /// whether typed characters filter the items, as select_word_with_filter() does
+    pub filter: bool,
+    /// whether a summary line `✓ description: item` is left on the selection, instead of the cleared screen
+    pub summary: bool,
+    /// keys to quit the selection (`q`, `Q` and Escape by default), which take precedence
+    /// over the navigation and the filter query
+    pub quit_keys: Vec<Key>,
+    /// feedback emitted when an item is selected (none by default)
+    pub feedback_on_submit: Option<Feedback>,
+    /// query to narrow the items down at the start, with `filter` set
+    pub initial_query: String,
+    /// dimmed help line below the items, such as LIST_HELP (none by default)
+    pub footer: Option<String>,
+    /// number of the first item, to show the items numbered like `1. Elephant` (none by default),
+    /// which counts the items shown after the filter
+    pub numbering: Option<usize>,
+    /// whether the selection runs in the alternate screen, which restores the previous screen on exit
+    pub alt_screen: bool,
+    /// symbol in front of the summary line (SUMMARY_SYMBOL by default)
+    pub summary_symbol: String,
+    /// how the selected item is highlighted (the leading marker by default)
+    pub highlight: HighlightStyle,
+    /// whether the query matches the items as a subsequence, like `elp` for `Elephant`,
+    /// with the items sorted by the match score, instead of as a substring
+    pub fuzzy: bool,
+    /// whether an item must be selected, where the quit keys ring the bell instead of quitting,
+    /// or are typed into the query if they are characters and `filter` is set
+    pub required: bool,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            filter: false,
+            summary: false,
+            quit_keys: vec![Key::Char('q'), Key::Char('Q'), Key::Escape],
+            feedback_on_submit: None,
+            initial_query: String::new(),
+            footer: None,
+            numbering: None,
+            alt_screen: false,
+            summary_symbol: SUMMARY_SYMBOL.to_string(),
+            highlight: HighlightStyle::Marker,
+            fuzzy: false,
+            required: false,
+        }
+    }
+}
+
+/// Highlight of the selected item in the item selection.
+///
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum HighlightStyle {
+    /// green `*` in front of the selected item
+    #[default]
+    Marker,
+    /// whole text of the selected item in reverse video, for monochrome terminals and screen readers
+    FullLine,
+}
+
+/// Key bindings of the item selection, to be shown as `ListOptions.footer`.
+pub const LIST_HELP: &str = "↑/↓ move • enter select • q quit";
+
+/// Clear the lines of the frame drawn last, leaving the cursor at the head of them,
+/// where the summary line replaces the frame without clearing the rest of the screen.
+///
+fn clear_frame(term: &Term, frame: &str) -> io::Result<()> {
+    term.clear_line()?;
+    term.clear_last_lines(frame.matches('\n').count())
+}
+
+/// Summary line `name: value` left on the selection, with the symbol in front if any.
+///
+fn summary_line(symbol: &str, name: &str, value: impl std::fmt::Display) -> String {
+    match symbol {
+        "" => format!("{}: {}", name, value),
+        _ => format!("{} {}: {}", symbol, name, value),
+    }
+}
+
+/// Item selection interface with options.
+///
+/// Items longer than the terminal width are cut off with an ellipsis,
+/// and the left/right arrow keys scroll the selected item horizontally.
+///
+/// ```rust,no_run
+/// use ttyui::selector::{select_word_with_options, ListOptions};
+///
+/// let animals = ["Elephant", "Horse", "Whale", "Tiger", "Panda"];
+/// let options = ListOptions {
+///     summary: true,
+///     ..Default::default()
+/// };
+/// select_word_with_options("your favorite animal", &animals, &options).unwrap();
+/// ```
+///
+pub fn select_word_with_options<S: AsRef<str>>(
+    description: &str,
+    items: &[S],
+    options: &ListOptions,
+) -> io::Result<String> {
+    match ListSelector::new(description)
+        .options(options.clone())
+        .select(items)?
+    {
+        SelectionOutcome::Selected { value, .. } => Ok(value),
+        SelectionOutcome::Quit => Err(io::Error::other(Quit)),
+    }
+}
+
+/// Item selector, which select_word_with_options() runs, as a Selector.
+///
+#[derive(Clone, Debug)]
+pub struct WordSelector<'a> {
+    description: &'a str,
+    items: Vec<&'a str>,
+    options: ListOptions,
+    /// index of the selected item in the visible items
+    seq: usize,
+    query: String,
+    hscroll: usize,
+    cancel: Option<Arc<AtomicBool>>,
+    term: Term,
+}
+
+impl<'a> WordSelector<'a> {
+    /// Generate a selector for the items.
+    ///
+    /// An empty slice of items results io::ErrorKind::InvalidInput.
+    ///
+    pub fn new<S: AsRef<str>>(
+        description: &'a str,
+        items: &'a [S],
+        options: ListOptions,
+    ) -> io::Result<Self> {
+        if items.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no items"));
+        }
+        let query = match options.filter {
+            true => options.initial_query.clone(),
+            false => String::new(),
+        };
+        Ok(WordSelector {
+            description,
+            items: items.iter().map(|item| item.as_ref()).collect(),
+            options,
+            seq: 0,
+            query,
+            hscroll: 0,
+            cancel: None,
+            term: Term::stdout(),
+        })
+    }
+
+    /// Generate a selector for the items, which draws on the terminal.
+    ///
+    pub fn with_term<S: AsRef<str>>(
+        description: &'a str,
+        items: &'a [S],
+        options: ListOptions,
+        term: Term,
+    ) -> io::Result<Self> {
+        let mut s = Self::new(description, items, options)?;
+        s.term = term;
+        Ok(s)
+    }
+
+    /// Set a flag to cancel the selection from another thread, which results
+    /// io::ErrorKind::Interrupted.
+    ///
+    pub fn set_cancel(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel = Some(flag);
+    }
+
+    /// Select the item equal to `item` at the start, if any.
+    ///
+    pub(crate) fn preselect(&mut self, item: &str) {
+        if let Some(i) = self.items.iter().position(|x| *x == item) {
+            self.seq = i;
+        }
+    }
+
+    /// Whether the items are scrolled horizontally, where Left scrolls them back.
+    ///
+    pub(crate) fn is_scrolled(&self) -> bool {
+        self.hscroll > 0
+    }
+
+    /// Index of the selected item in the items, not in the visible ones.
+    ///
+    pub fn selected_index(&self) -> Option<usize> {
+        self.visible_indices().get(self.seq).copied()
+    }
+
+    /// Current frame of the selection, as render() draws it.
+    ///
+    fn frame(&self) -> String {
+        render_frame(
+            self.description,
+            &self.visible(),
+            self.seq,
+            &self.query,
+            self.hscroll,
+            &self.options,
+            self.term.size().1 as usize,
+        )
+    }
+
+    /// Columns for an item, besides the marker and the number.
+    ///
+    fn width(&self) -> usize {
+        let numbers = number_width(&self.options, self.visible().len());
+        item_width(self.term.size().1 as usize)
+            .saturating_sub(numbers)
+            .max(1)
+    }
+
+    /// Items matching the query.
+    ///
+    fn visible(&self) -> Vec<&'a str> {
+        self.visible_indices()
+            .into_iter()
+            .map(|i| self.items[i])
+            .collect()
+    }
+
+    /// Indices of the items matching the query, in the order they are shown.
+    ///
+    /// The fuzzy matches are sorted by the score, where the ties keep the list order.
+    ///
+    fn visible_indices(&self) -> Vec<usize> {
+        if !self.options.fuzzy {
+            return (0..self.items.len())
+                .filter(|i| find_ignore_case(self.items[*i], &self.query).is_some())
+                .collect();
+        }
+        let mut scored: Vec<(usize, i64)> = (0..self.items.len())
+            .filter_map(|i| fuzzy_match(self.items[i], &self.query).map(|(score, _)| (i, score)))
+            .collect();
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+}
+
+impl Selector for WordSelector<'_> {
+    type Output = String;
+
+    fn term(&self) -> &Term {
+        &self.term
+    }
+
+    fn cancel_flag(&self) -> Option<&AtomicBool> {
+        self.cancel.as_deref()
+    }
+
+    /// Run the selection, in the alternate screen if `alt_screen` is set,
+    /// which is left on any exit.
+    fn select(&mut self) -> io::Result<String> {
+        if !self.options.alt_screen {
+            return run_selection(self);
+        }
+        // the summary is left on the main screen, after the alternate one is left
+        let summary = std::mem::take(&mut self.options.summary);
+        self.term.write_str("\x1b[?1049h")?;
+        let res = run_selection(self);
+        self.options.summary = summary;
+        self.term.write_str("\x1b[?1049l")?;
+        let value = res?;
+        if summary {
+            self.term.write_line(&summary_line(
+                &self.options.summary_symbol,
+                self.description,
+                &value,
+            ))?;
+        }
+        Ok(value)
+    }
+
+    fn render(&self) -> io::Result<()> {
+        self.term.clear_screen()?;
+        self.term.write_str(&self.frame())
+    }
+
+    fn handle_key(&mut self, key: Key) -> io::Result<Option<String>> {
+        if self.options.quit_keys.contains(&key) && self.options.required {
+            let typed = matches!(key, Key::Char(c) if self.options.filter && !c.is_control());
+            if !typed {
+                self.term.write_str("\x07")?;
+                return Ok(None);
+            }
+        } else if self.options.quit_keys.contains(&key) {
+            self.term.clear_screen()?;
+            return Err(io::Error::other(Quit));
+        }
+        let visible = self.visible();
+        let prev = (self.seq, self.query.len());
+        self.seq = match key {
+            Key::ArrowRight if !visible.is_empty() => {
+                if is_cut_off(visible[self.seq], self.hscroll, self.width()) {
+                    self.hscroll += 1;
+                }
+                self.seq
+            }
+            Key::ArrowLeft => {
+                self.hscroll = self.hscroll.saturating_sub(1);
+                self.seq
+            }
+            Key::Char('k') if !self.options.filter => prev_seq(self.seq, visible.len()),
+            Key::Char('j') if !self.options.filter => next_seq(self.seq, visible.len()),
+            Key::ArrowUp => prev_seq(self.seq, visible.len()),
+            Key::ArrowDown => next_seq(self.seq, visible.len()),
+            Key::Enter if !visible.is_empty() => {
+                if self.options.summary {
+                    clear_frame(&self.term, &self.frame())?;
+                    self.term.write_line(&summary_line(
+                        &self.options.summary_symbol,
+                        self.description,
+                        visible[self.seq],
+                    ))?;
+                } else {
+                    self.term.clear_screen()?;
+                }
+                if let Some(feedback) = self.options.feedback_on_submit {
+                    feedback.emit(&self.term)?;
+                }
+                return Ok(Some(String::from(visible[self.seq])));
+            }
+            Key::Backspace if self.options.filter => {
+                self.query.pop();
+                0
+            }
+            Key::Char(c) if self.options.filter && !c.is_control() => {
+                self.query.push(c);
+                0
+            }
+            _ => self.seq,
+        };
+        if prev != (self.seq, self.query.len()) {
+            self.hscroll = 0;
+        }
+        Ok(None)
+    }
+}
+
+/// Item selection where each row is drawn by `render`, which takes the index of the item and
+/// whether it is selected, and returns the line to be printed.
+///
+/// This returns the selected index, or io::Error::Other for `Q` or escape key pressed.
+/// Zero items result io::ErrorKind::InvalidInput.
+///
+/// ```rust,no_run
+/// use ttyui::selector::select_with_renderer;
+///
+/// let files = [("README.md", 1024), ("Cargo.toml", 512)];
+/// let i = select_with_renderer("file to open", files.len(), |i, selected| {
+///     let (name, size) = files[i];
+///     let mark = if selected { ">" } else { " " };
+///     format!("{} {:<12}{:>6} B", mark, name, size)
+/// })
+/// .unwrap();
+/// println!("opening {}", files[i].0);
+/// ```
+///
+pub fn select_with_renderer(
+    prompt: &str,
+    count: usize,
+    render: impl Fn(usize, bool) -> String,
+) -> io::Result<usize> {
+    if count == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no items"));
+    }
+    let term = Term::stdout();
+    let mut seq = 0;
+    loop {
+        term.clear_screen()?;
+        let mut out = format!("{}\n", prompt);
+        for i in 0..count {
+            out += &render(i, i == seq);
+            out.push('\n');
+        }
+        term.write_str(&out)?;
+        let Some(key) = read_plain_key_or_resize(&term, None)? else {
+            continue;
+        };
+        seq = match key {
+            Key::Char('q') | Key::Char('Q') | Key::Escape => {
+                term.clear_screen()?;
+                return Err(io::Error::other(Quit));
+            }
+            Key::ArrowUp | Key::Char('k') => prev_seq(seq, count),
+            Key::ArrowDown | Key::Char('j') => next_seq(seq, count),
+            Key::Enter => {
                 term.clear_screen()?;
-                return Err(io::Error::new(io::ErrorKind::Other, "quit"));
+                return Ok(seq);
+            }
+            _ => seq,
+        };
+    }
+}
+
+/// Checkbox selection of the items, where Space toggles the item under the cursor
+/// and `a` checks all the items, or unchecks all if they are already checked.
+///
+/// This returns the indices of the checked items in the list order, or io::Error::Other for `Q` or escape key pressed.
+/// An empty slice of items results io::ErrorKind::InvalidInput.
+///
+/// ```rust,no_run
+/// use ttyui::selector::select_multiple;
+///
+/// let animals = ["Elephant", "Horse", "Whale"];
+/// for i in select_multiple("animals to feed", &animals).unwrap() {
+///     println!("feeding {}", animals[i]);
+/// }
+/// ```
+///
+pub fn select_multiple<S: AsRef<str>>(description: &str, items: &[S]) -> io::Result<Vec<usize>> {
+    MultiSelector::new(description, items)?.select()
+}
+
+/// Checkbox selection of the items, which returns the checked items themselves in the list order.
+///
+/// ```rust,no_run
+/// use ttyui::selector::select_multiple_values;
+///
+/// let animals = ["Elephant", "Horse", "Whale"];
+/// println!("{:?}", select_multiple_values("animals to feed", &animals).unwrap());
+/// ```
+///
+pub fn select_multiple_values<S: AsRef<str>>(
+    description: &str,
+    items: &[S],
+) -> io::Result<Vec<String>> {
+    Ok(select_multiple(description, items)?
+        .into_iter()
+        .map(|i| items[i].as_ref().to_string())
+        .collect())
+}
+
+/// Checkbox selector, which select_multiple() runs, as a Selector.
+///
+#[derive(Clone, Debug)]
+pub struct MultiSelector<'a> {
+    description: &'a str,
+    items: Vec<&'a str>,
+    checked: Vec<bool>,
+    /// indices of the checked items in the order they were checked
+    order: Vec<usize>,
+    /// index of the item under the cursor
+    seq: usize,
+    cancel: Option<Arc<AtomicBool>>,
+    term: Term,
+    /// whether the indices are returned in the order the items were checked, rather than the list order,
+    /// which moves an item checked again to the end
+    pub preserve_selection_order: bool,
+    /// whether a summary line `✓ description: item, item` is left on the selection, instead of the cleared screen
+    pub summary: bool,
+    /// symbol in front of the summary line (SUMMARY_SYMBOL by default)
+    pub summary_symbol: String,
+}
+
+impl<'a> MultiSelector<'a> {
+    /// Generate a selector for the items, all of which are unchecked.
+    ///
+    /// An empty slice of items results io::ErrorKind::InvalidInput.
+    ///
+    pub fn new<S: AsRef<str>>(description: &'a str, items: &'a [S]) -> io::Result<Self> {
+        if items.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no items"));
+        }
+        Ok(MultiSelector {
+            description,
+            items: items.iter().map(|item| item.as_ref()).collect(),
+            checked: vec![false; items.len()],
+            order: Vec::new(),
+            seq: 0,
+            cancel: None,
+            term: Term::stdout(),
+            preserve_selection_order: false,
+            summary: false,
+            summary_symbol: SUMMARY_SYMBOL.to_string(),
+        })
+    }
+
+    /// Generate a selector for the items, which draws on the terminal.
+    ///
+    pub fn with_term<S: AsRef<str>>(
+        description: &'a str,
+        items: &'a [S],
+        term: Term,
+    ) -> io::Result<Self> {
+        let mut s = Self::new(description, items)?;
+        s.term = term;
+        Ok(s)
+    }
+
+    /// Set a flag to cancel the selection from another thread, which results
+    /// io::ErrorKind::Interrupted.
+    ///
+    pub fn set_cancel(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel = Some(flag);
+    }
+
+    /// Indices of the checked items in the list order, or in the order they were checked
+    /// with `preserve_selection_order`.
+    ///
+    pub fn checked_indices(&self) -> Vec<usize> {
+        if self.preserve_selection_order {
+            return self.order.clone();
+        }
+        (0..self.items.len()).filter(|i| self.checked[*i]).collect()
+    }
+
+    /// Current frame of the selection, as render() draws it.
+    ///
+    fn frame(&self) -> String {
+        render_checkboxes(
+            self.description,
+            &self.items,
+            &self.checked,
+            self.seq,
+            self.term.size().1 as usize,
+        )
+    }
+
+    /// Check or uncheck the item at `i`, keeping the order of the checked items.
+    ///
+    fn set_checked(&mut self, i: usize, checked: bool) {
+        if self.checked[i] == checked {
+            return;
+        }
+        self.checked[i] = checked;
+        if checked {
+            self.order.push(i);
+        } else {
+            self.order.retain(|j| *j != i);
+        }
+    }
+}
+
+impl Selector for MultiSelector<'_> {
+    type Output = Vec<usize>;
+
+    fn term(&self) -> &Term {
+        &self.term
+    }
+
+    fn cancel_flag(&self) -> Option<&AtomicBool> {
+        self.cancel.as_deref()
+    }
+
+    fn render(&self) -> io::Result<()> {
+        self.term.clear_screen()?;
+        self.term.write_str(&self.frame())
+    }
+
+    fn handle_key(&mut self, key: Key) -> io::Result<Option<Vec<usize>>> {
+        match key {
+            Key::Char('q') | Key::Char('Q') | Key::Escape => {
+                self.term.clear_screen()?;
+                return Err(io::Error::other(Quit));
+            }
+            Key::ArrowUp | Key::Char('k') => self.seq = prev_seq(self.seq, self.items.len()),
+            Key::ArrowDown | Key::Char('j') => self.seq = next_seq(self.seq, self.items.len()),
+            Key::Char(' ') => self.set_checked(self.seq, !self.checked[self.seq]),
+            Key::Char('a') | Key::Char('A') => {
+                let all = self.checked.iter().all(|c| *c);
+                for i in 0..self.items.len() {
+                    self.set_checked(i, !all);
+                }
+            }
+            Key::Enter => {
+                let indices = self.checked_indices();
+                if self.summary {
+                    let items: Vec<&str> = indices.iter().map(|i| self.items[*i]).collect();
+                    clear_frame(&self.term, &self.frame())?;
+                    self.term.write_line(&summary_line(
+                        &self.summary_symbol,
+                        self.description,
+                        items.join(", "),
+                    ))?;
+                } else {
+                    self.term.clear_screen()?;
+                }
+                return Ok(Some(indices));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+/// Render a frame of the checkbox selection for the terminal `columns` wide.
+///
+fn render_checkboxes(
+    description: &str,
+    items: &[&str],
+    checked: &[bool],
+    seq: usize,
+    columns: usize,
+) -> String {
+    let width = item_width(columns).saturating_sub(4).max(1);
+    let mut out = String::new();
+    for line in description.split('\n') {
+        out += &fit_item(line, "", 0, columns.max(1));
+        out.push('\n');
+    }
+    let table = mark_table(items.len(), seq);
+    for ((mark, item), checked) in table.iter().zip(items).zip(checked) {
+        let checkbox = if *checked { "[x]" } else { "[ ]" };
+        out += &format!("{} {} {}\n", mark, checkbox, fit_item(item, "", 0, width));
+    }
+    out
+}
+
+/// Render a frame of the item selection into a string, as the selector prints it
+/// for the terminal `width` columns wide.
+///
+/// This is useful for snapshot tests of a menu layout, without driving a terminal.
+///
+/// ```rust
+/// use ttyui::selector::{render_list, ListOptions};
+///
+/// let frame = render_list("animal", &["Elephant", "Horse"], 1, &ListOptions::default(), 80);
+/// assert_eq!(frame, "animal\n  Elephant\n\x1b[32m*\x1b[0m Horse\n");
+/// ```
+///
+pub fn render_list<S: AsRef<str>>(
+    description: &str,
+    items: &[S],
+    selected: usize,
+    options: &ListOptions,
+    width: usize,
+) -> String {
+    let items: Vec<&str> = items.iter().map(|item| item.as_ref()).collect();
+    render_frame(description, &items, selected, "", 0, options, width)
+}
+
+/// Render a frame of the visible items, with the selected one scrolled by `hscroll`.
+///
+/// Each line of the description is cut off at the terminal `columns`, not to be wrapped.
+///
+fn render_frame(
+    description: &str,
+    visible: &[&str],
+    seq: usize,
+    query: &str,
+    hscroll: usize,
+    options: &ListOptions,
+    columns: usize,
+) -> String {
+    let numbers = number_width(options, visible.len());
+    let width = item_width(columns).saturating_sub(numbers).max(1);
+    let mut out = String::new();
+    for line in description.split('\n') {
+        out += &fit_item(line, "", 0, columns.max(1));
+        out.push('\n');
+    }
+    if options.filter {
+        out += &format!("> {}\n", query);
+    }
+    let table = match options.highlight {
+        HighlightStyle::Marker => mark_table(visible.len(), seq),
+        HighlightStyle::FullLine => vec![" "; visible.len()],
+    };
+    for (i, (mark, item)) in table.iter().zip(visible).enumerate() {
+        let skip = if i == seq { hscroll } else { 0 };
+        let number = match options.numbering {
+            Some(first) => format!("{:>w$}. ", first + i, w = numbers - 2),
+            None => String::new(),
+        };
+        // the lines of a multi-line item after the first are indented under it, without the marker
+        for (n, line) in item.split('\n').enumerate() {
+            let mut text = fit_matched(line, query, options.fuzzy, skip, width);
+            if i == seq && options.highlight == HighlightStyle::FullLine {
+                // the reset closing the query match would end the reverse video in the middle
+                text = format!("\x1b[7m{}\x1b[0m", text.replace("\x1b[0m", "\x1b[0;7m"));
             }
-            Key::Enter => {
-                term.clear_screen()?;
-                return Ok(String::from(items[seq]));
+            match n {
+                0 => out += &format!("{} {}{}\n", mark, number, text),
+                _ => out += &format!("{:w$}{}\n", "", text, w = 2 + numbers),
             }
-            _ => seq,
-        };
+        }
+    }
+    if let Some(footer) = &options.footer {
+        out += &format!(
+            "\x1b[2m{}\x1b[0m\n",
+            fit_item(footer, "", 0, columns.max(1))
+        );
+    }
+    out
+}
 
-        for i in 0..word_count {
-            if i == seq {
-                table[i] = "\x1b[32m*\x1b[0m";
-            } else {
-                table[i] = " ";
+/// Columns for an item in the terminal `width` columns wide, besides the marker.
+///
+fn item_width(width: usize) -> usize {
+    width.saturating_sub(2).max(1)
+}
+
+/// Columns for the numbers of `count` items followed by `. `, or 0 without `numbering`.
+///
+fn number_width(options: &ListOptions, count: usize) -> usize {
+    match options.numbering {
+        Some(first) => (first + count.saturating_sub(1)).to_string().len() + 2,
+        None => 0,
+    }
+}
+
+/// Fit an item into `width` columns, skipping `skip` characters from the head for the horizontal scroll.
+///
+/// The item is cut on character boundaries, and the cut ends are marked with an ellipsis.
+///
+fn fit_item(item: &str, query: &str, skip: usize, width: usize) -> String {
+    fit_matched(item, query, false, skip, width)
+}
+
+/// fit_item() with the query highlighted as a subsequence for `fuzzy`.
+///
+fn fit_matched(item: &str, query: &str, fuzzy: bool, skip: usize, width: usize) -> String {
+    let shown = match item.char_indices().nth(skip) {
+        Some((i, _)) => &item[i..],
+        None => "",
+    };
+    let head = if skip > 0 { "…" } else { "" };
+    let highlighted = match fuzzy {
+        true => highlight_fuzzy(shown, query),
+        false => highlight_match(shown, query),
+    };
+    let line = format!("{}{}", head, highlighted);
+    if measure_text_width(&line) > width {
+        truncate_str(&line, width, "…").into_owned()
+    } else {
+        line
+    }
+}
+
+/// Whether the tail of an item is cut off, for the horizontal scroll of `skip` characters.
+///
+fn is_cut_off(item: &str, skip: usize, width: usize) -> bool {
+    item.split('\n')
+        .any(|line| fit_item(line, "", skip, width) != fit_item(line, "", skip, usize::MAX))
+}
+
+/// Ring-bufferish previous index of the selection among `count` items.
+///
+fn prev_seq(seq: usize, count: usize) -> usize {
+    match seq {
+        _ if count == 0 => 0,
+        0 => count - 1,
+        _ => seq - 1,
+    }
+}
+
+/// Ring-bufferish next index of the selection among `count` items.
+///
+fn next_seq(seq: usize, count: usize) -> usize {
+    if seq + 1 >= count {
+        0
+    } else {
+        seq + 1
+    }
+}
+
+/// Find `query` in `item` case-insensitively, and return the byte range of the match in `item`.
+///
+fn find_ignore_case(item: &str, query: &str) -> Option<(usize, usize)> {
+    if query.is_empty() {
+        return Some((0, 0));
+    }
+    for (start, _) in item.char_indices() {
+        let mut rest = item[start..].char_indices();
+        let mut end = start;
+        let mut matched = true;
+        for q in query.chars() {
+            match rest.next() {
+                Some((i, c)) if c.to_lowercase().eq(q.to_lowercase()) => {
+                    end = start + i + c.len_utf8();
+                }
+                _ => {
+                    matched = false;
+                    break;
+                }
+            }
+        }
+        if matched {
+            return Some((start, end));
+        }
+    }
+    None
+}
+
+/// Highlight the part of `item` matched with `query`.
+///
+/// The escape sequences are closed within the item, not to leak into the marker of the next line.
+///
+fn highlight_match(item: &str, query: &str) -> String {
+    match find_ignore_case(item, query) {
+        Some((start, end)) if start < end => format!(
+            "{}\x1b[1;33m{}\x1b[0m{}",
+            &item[..start],
+            &item[start..end],
+            &item[end..]
+        ),
+        _ => item.to_string(),
+    }
+}
+
+/// Match `query` in `item` as a subsequence case-insensitively, and return the score
+/// and the byte indices of the matched characters in `item`.
+///
+/// Each matched character scores, with bonuses for following the previous match
+/// and for the head of a word, so that the tightly packed matches rank first.
+/// The match is tried from each character, and the best one is taken.
+///
+fn fuzzy_match(item: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for (start, _) in item.char_indices() {
+        if let Some(m) = fuzzy_match_from(item, query, start) {
+            if best.as_ref().is_none_or(|(score, _)| m.0 > *score) {
+                best = Some(m);
+            }
+        }
+    }
+    best.or_else(|| query.is_empty().then(|| (0, Vec::new())))
+}
+
+/// Match `query` greedily in `item` from the byte index `start`.
+///
+fn fuzzy_match_from(item: &str, query: &str, start: usize) -> Option<(i64, Vec<usize>)> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut matched = Vec::new();
+    let mut prev = item[..start].chars().next_back();
+    let mut last = None;
+    for (n, (i, c)) in item[start..].char_indices().enumerate() {
+        let Some(q) = query.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(*q)) {
+            score += 1;
+            if last.is_some_and(|last| last + 1 == n) {
+                score += 4;
             }
+            if prev.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 2;
+            }
+            matched.push(start + i);
+            last = Some(n);
+            query.next();
+        }
+        prev = Some(c);
+    }
+    match query.peek() {
+        Some(_) => None,
+        None => Some((score, matched)),
+    }
+}
+
+/// Highlight the characters of `item` matched with `query` as a subsequence.
+///
+/// The consecutive matched characters are highlighted together.
+///
+fn highlight_fuzzy(item: &str, query: &str) -> String {
+    let Some((_, matched)) = fuzzy_match(item, query) else {
+        return item.to_string();
+    };
+    let mut out = String::new();
+    let mut open = false;
+    for (i, c) in item.char_indices() {
+        let hit = matched.binary_search(&i).is_ok();
+        if hit && !open {
+            out += "\x1b[1;33m";
+        } else if !hit && open {
+            out += "\x1b[0m";
+        }
+        open = hit;
+        out.push(c);
+    }
+    if open {
+        out += "\x1b[0m";
+    }
+    out
+}
+
+/// Markers for each line of the item selection, with the selected item at `seq`.
+///
+fn mark_table(word_count: usize, seq: usize) -> Vec<&'static str> {
+    (0..word_count)
+        .map(|i| if i == seq { "\x1b[32m*\x1b[0m" } else { " " })
+        .collect()
+}
+
+#[cfg(test)]
+mod date_time_field_tests {
+    use crate::selector::DateTimeField;
+
+    #[test]
+    fn all_fields_are_ordered_by_next() {
+        let fields = DateTimeField::all_fields();
+        for (i, f) in fields.iter().enumerate() {
+            assert_eq!(f.next(), fields[(i + 1) % fields.len()]);
+        }
+    }
+
+    #[test]
+    fn prev_reverts_next() {
+        for f in DateTimeField::all_fields() {
+            assert_eq!(f.next().prev(), f);
         }
     }
 }
@@ -441,135 +2783,511 @@ mod date_selector_tests {
 
     fn datetime_init() -> (DateSelector, DateSelector) {
         let mut o = DateSelector::new();
-        o.has_time = true;
+        o.set_has_time(true);
         (o.clone(), o)
     }
 
     #[test]
-    fn date_up_increments_day_by_default() {
+    fn date_up_increments_day_by_default() {
+        let (mut t, s) = date_init();
+        t.up().unwrap();
+        assert_eq!(t.get_date(), s.get_date() + Duration::days(1))
+    }
+
+    #[test]
+    fn date_down_decrements_day_by_default() {
+        let (mut t, s) = date_init();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date() - Duration::days(1))
+    }
+
+    #[test]
+    fn date_left_down2_decrements_months() {
+        let (mut t, s) = date_init();
+        t.left().unwrap();
+        t.down().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date() - Months::new(2))
+    }
+
+    #[test]
+    fn date_left_up_down_results_same_date() {
+        let (mut t, s) = date_init();
+        t.left().unwrap();
+        t.up().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date())
+    }
+
+    #[test]
+    fn date_left2_down_decrements_year() {
+        let (mut t, s) = date_init();
+        t.left().unwrap();
+        t.left().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date() - Months::new(12))
+    }
+
+    #[test]
+    fn date_left3_down_decrements_day() {
+        let (mut t, s) = date_init();
+        t.left().unwrap();
+        t.left().unwrap();
+        t.left().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.active_field, s.active_field);
+        assert_eq!(t.get_date(), s.get_date() - Duration::days(1));
+    }
+
+    #[test]
+    fn datetime_left3_down_decrements_second() {
+        let (mut t, s) = datetime_init();
+        t.left().unwrap();
+        t.left().unwrap();
+        t.left().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date() - Duration::seconds(1))
+    }
+
+    #[test]
+    fn date_right_left_up_increments_day() {
+        let (mut t, s) = date_init();
+        t.right().unwrap();
+        t.left().unwrap();
+        t.up().unwrap();
+        assert_eq!(t.get_date(), s.get_date() + Duration::days(1))
+    }
+
+    #[test]
+    fn datetime_right_left_up_increments_day() {
+        let (mut t, s) = datetime_init();
+        t.right().unwrap();
+        t.left().unwrap();
+        t.up().unwrap();
+        assert_eq!(t.get_date(), s.get_date() + Duration::days(1))
+    }
+
+    #[test]
+    fn date_right_up_increments_year() {
+        let (mut t, s) = date_init();
+        t.right().unwrap();
+        t.up().unwrap();
+        assert_eq!(t.get_date(), s.get_date() + Months::new(12))
+    }
+
+    #[test]
+    fn datetime_right_up_increments_hour() {
+        let (mut t, s) = datetime_init();
+        t.right().unwrap();
+        t.up().unwrap();
+        assert_eq!(t.get_date(), s.get_date() + Duration::hours(1))
+    }
+
+    #[test]
+    fn datetime_right2_down2_decrements_minutes() {
+        let (mut t, s) = datetime_init();
+        t.right().unwrap();
+        t.right().unwrap();
+        t.down().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date() - Duration::minutes(2))
+    }
+
+    #[test]
+    fn datetime_right3_up_increments_second() {
+        let (mut t, s) = datetime_init();
+        t.right().unwrap();
+        t.right().unwrap();
+        t.right().unwrap();
+        t.up().unwrap();
+        assert_eq!(t.get_date(), s.get_date() + Duration::seconds(1))
+    }
+
+    #[test]
+    fn datetime_right4_down_decrements_year() {
+        let (mut t, s) = datetime_init();
+        t.right().unwrap();
+        t.right().unwrap();
+        t.right().unwrap();
+        t.right().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.get_date(), s.get_date() - Months::new(12))
+    }
+
+    #[test]
+    fn test_date_confirm_keys_default_to_enter() {
+        let (t, _) = date_init();
+        assert_eq!(t.confirm_keys, vec![Key::Enter]);
+    }
+
+    #[test]
+    fn test_date_set_active_field_year_then_up_increments_year() {
+        let (mut t, s) = date_init();
+        t.set_active_field(DateTimeField::Year).unwrap();
+        t.up().unwrap();
+        assert_eq!(t.date, s.date + Months::new(12));
+    }
+
+    #[test]
+    fn test_date_set_active_field_rejects_time_field_without_time() {
+        let (mut t, _) = date_init();
+        assert!(t.set_active_field(DateTimeField::Hour).is_err());
+        assert_eq!(t.active_field, DateTimeField::Day);
+        t.set_has_time(true);
+        assert!(t.set_active_field(DateTimeField::Hour).is_ok());
+    }
+
+    #[test]
+    fn test_date_parse_keeps_time_for_date_only() {
+        let (t, s) = date_init();
+        let d = t.parse("2024-12-31").unwrap();
+        assert_eq!(d.format("%Y-%m-%d").to_string(), "2024-12-31");
+        assert_eq!(d.time(), s.date.time());
+        assert!(t.parse("2024-13-01").is_none());
+        assert!(t.parse("2024-12-31 10:00:00").is_none());
+    }
+
+    #[test]
+    fn test_datetime_parse_results_full_datetime() {
+        let (t, _) = datetime_init();
+        let d = t.parse("2024-12-31 23:59:58").unwrap();
+        assert_eq!(d.format(DATETIME_FORMAT).to_string(), "2024-12-31 23:59:58");
+        assert!(t.parse("2024-12-31").is_none());
+    }
+
+    #[test]
+    fn test_active_field_value_follows_field() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 12, 31, 23, 59, 58).unwrap());
+        t.set_has_time(true);
+        assert_eq!(t.active_field_value(), (DateTimeField::Day, 31));
+        t.left().unwrap();
+        assert_eq!(t.active_field_value(), (DateTimeField::Month, 12));
+        t.left().unwrap();
+        assert_eq!(t.active_field_value(), (DateTimeField::Year, 2024));
+        t.set_active_field(DateTimeField::Second).unwrap();
+        assert_eq!(t.active_field_value(), (DateTimeField::Second, 58));
+    }
+
+    #[test]
+    fn test_date_stays_within_year_range() {
+        let (mut t, s) = date_init();
+        t.year_range = s.date.year()..=s.date.year();
+        t.set_active_field(DateTimeField::Year).unwrap();
+        t.up().unwrap();
+        t.down().unwrap();
+        assert_eq!(t.date, s.date);
+    }
+
+    #[test]
+    fn test_date_stops_at_chrono_limit() {
+        let max = NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap();
+        let (mut t, _) = date_init();
+        t.set_date(Local.from_local_datetime(&max).unwrap());
+        let before = t.date;
+        t.up().unwrap();
+        assert_eq!(t.date, before);
+    }
+
+    #[test]
+    fn test_wide_year_keeps_cursor_on_field() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(12345, 6, 7, 0, 0, 0).unwrap());
+        t.name = "day".to_string();
+        assert_eq!(t.to_string(), "+12345-06-07");
+        assert_eq!(t.cursor_column(), "day: +12345-06-0".len());
+        t.set_active_field(DateTimeField::Year).unwrap();
+        assert_eq!(t.cursor_column(), "day: +1234".len());
+    }
+
+    #[test]
+    fn test_date_stays_within_min_and_max() {
         let (mut t, s) = date_init();
+        t.min = Bound::Included(s.date);
+        t.max = Bound::Included(s.date + Duration::days(1));
+        t.down().unwrap();
+        assert_eq!(t.date, s.date);
         t.up().unwrap();
-        assert_eq!(t.get_date(), s.get_date() + Duration::days(1))
+        t.up().unwrap();
+        assert_eq!(t.date, s.date + Duration::days(1));
+        t.max = Bound::Included(s.date);
+        t.normalize();
+        assert_eq!(t.date, s.date);
     }
 
     #[test]
-    fn date_down_decrements_day_by_default() {
-        let (mut t, s) = date_init();
-        t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date() - Duration::days(1))
+    fn test_add_saturates_at_year_range_and_bounds() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 5, 31, 12, 0, 0).unwrap());
+        t.set_has_time(true);
+        t.add_hours(13);
+        assert_eq!(t.to_string(), "2024-06-01 01:00:00");
+        assert_eq!(t.active_field, DateTimeField::Day);
+        t.year_range = 2000..=2030;
+        t.add_years(100);
+        assert_eq!(t.to_string(), "2030-06-01 01:00:00");
+        t.min = Bound::Included(Local.with_ymd_and_hms(2029, 1, 1, 0, 0, 0).unwrap());
+        t.add_days(-10000);
+        assert_eq!(t.to_string(), "2029-01-01 00:00:00");
+        t.add_seconds(i64::MIN);
+        assert_eq!(t.to_string(), "2029-01-01 00:00:00");
     }
 
     #[test]
-    fn date_left_down2_decrements_months() {
+    fn test_format_date_with_invalid_pattern_is_empty() {
+        let (t, _) = date_init();
+        assert_eq!(t.format_date("%Q"), "");
+        assert_eq!(t.format_date("%Y-%m-%d"), t.to_string());
+    }
+
+    #[test]
+    fn test_pasted_date_is_applied() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 5, 31, 12, 0, 0).unwrap());
+        t.apply_typed("2025-01-15");
+        assert_eq!(
+            t.date,
+            Local.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap()
+        );
+        t.apply_typed("2025-01-");
+        assert_eq!(t.to_string(), "2025-01-15");
+        assert!(t.message.is_some());
+        t.handle_key(Key::Char('7')).unwrap();
+        assert_eq!(t.to_string(), "2025-01-15");
+        assert!(t.message.is_none());
+    }
+
+    #[test]
+    fn test_excluded_bounds_are_not_selectable() {
         let (mut t, s) = date_init();
-        t.left().unwrap();
-        t.down().unwrap();
+        t.min = Bound::Excluded(s.date);
+        t.normalize();
+        assert_eq!(t.date, s.date + Duration::days(1));
         t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date() - Months::new(2))
+        assert_eq!(t.date, s.date + Duration::days(1));
+        t.set_has_time(true);
+        t.date = s.date;
+        t.normalize();
+        assert_eq!(t.date, s.date + Duration::seconds(1));
+        t.max = Bound::Excluded(s.date + Duration::seconds(1));
+        assert_eq!(t.handle_key(Key::Enter).unwrap(), None);
     }
 
     #[test]
-    fn date_left_up_down_results_same_date() {
-        let (mut t, s) = date_init();
-        t.left().unwrap();
+    fn test_disabled_weekdays_are_skipped() {
+        // Friday
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 5, 31, 12, 0, 0).unwrap());
+        t.disabled_weekdays = vec![Weekday::Sat, Weekday::Sun];
         t.up().unwrap();
+        assert_eq!(t.to_string(), "2024-06-03");
         t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date())
+        assert_eq!(t.to_string(), "2024-05-31");
+        t.set_date(Local.with_ymd_and_hms(2024, 6, 2, 12, 0, 0).unwrap());
+        t.normalize();
+        assert_eq!(t.to_string(), "2024-06-03");
     }
 
     #[test]
-    fn date_left2_down_decrements_year() {
-        let (mut t, s) = date_init();
-        t.left().unwrap();
-        t.left().unwrap();
-        t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date() - Months::new(12))
+    fn test_disabled_weekday_is_not_confirmed() {
+        // Sunday
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 6, 2, 12, 0, 0).unwrap());
+        t.disabled_weekdays = vec![Weekday::Sun];
+        assert_eq!(t.handle_key(Key::Enter).unwrap(), None);
+        assert_eq!(t.message.as_deref(), Some("Sun is not available"));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn date_left3_down_decrements_day() {
-        let (mut t, s) = date_init();
-        t.left().unwrap();
-        t.left().unwrap();
-        t.left().unwrap();
-        t.down().unwrap();
-        assert_eq!(t.active_field, s.active_field);
-        assert_eq!(t.get_date(), s.get_date() - Duration::days(1));
+    fn test_typed_date_is_abandoned_on_cancel_and_eof() {
+        use crate::readline::Action;
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("date-type-abandon");
+        let mut t = DateSelector::with_term(term.clone());
+        let s = t.date;
+        // the key read from a non-tty is Key::Unknown
+        for action in [Action::Cancel, Action::Eof] {
+            let mut buf = Buffer::with_term(term.clone());
+            buf.bind(Key::Unknown, action);
+            t.type_date_with(buf).unwrap();
+            assert_eq!(t.date, s);
+        }
+        output(&path);
     }
 
+    #[cfg(unix)]
     #[test]
-    fn datetime_left3_down_decrements_second() {
-        let (mut t, s) = datetime_init();
-        t.left().unwrap();
-        t.left().unwrap();
-        t.left().unwrap();
-        t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date() - Duration::seconds(1))
+    fn test_selector_select_normalizes_date() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("date-normalize");
+        let mut t = DateSelector::with_term(term);
+        let s = t.date;
+        t.min = Bound::Included(s + Duration::days(1));
+        t.set_cancel(Arc::new(AtomicBool::new(true)));
+        let e = Selector::select(&mut t).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::Interrupted);
+        assert_eq!(t.date, s + Duration::days(1));
+        drop(t);
+        output(&path);
     }
 
     #[test]
-    fn date_right_left_up_increments_day() {
-        let (mut t, s) = date_init();
-        t.right().unwrap();
-        t.left().unwrap();
+    fn test_left_right_adjusts_in_swapped_scheme() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 5, 31, 12, 0, 0).unwrap());
+        t.navigation_scheme = NavigationScheme::LeftRightAdjusts;
+        t.handle_key(Key::ArrowRight).unwrap();
+        assert_eq!(t.to_string(), "2024-06-01");
+        t.handle_key(Key::ArrowUp).unwrap();
+        assert_eq!(t.active_field, DateTimeField::Month);
+        t.handle_key(Key::ArrowLeft).unwrap();
+        assert_eq!(t.to_string(), "2024-05-01");
+    }
+
+    #[test]
+    fn test_home_and_end_jump_to_first_and_last_fields() {
+        let (mut t, _) = date_init();
+        t.handle_key(Key::End).unwrap();
+        assert_eq!(t.active_field, DateTimeField::Day);
+        t.handle_key(Key::Home).unwrap();
+        assert_eq!(t.active_field, DateTimeField::Year);
+        t.set_has_time(true);
+        t.handle_key(Key::End).unwrap();
+        assert_eq!(t.active_field, DateTimeField::Second);
+    }
+
+    #[test]
+    fn test_day_wraps_in_month() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap());
+        t.day_wraps_in_month = true;
         t.up().unwrap();
-        assert_eq!(t.get_date(), s.get_date() + Duration::days(1))
+        assert_eq!(t.to_string(), "2024-02-01");
+        t.down().unwrap();
+        assert_eq!(t.to_string(), "2024-02-29");
+        t.day_wraps_in_month = false;
+        t.up().unwrap();
+        assert_eq!(t.to_string(), "2024-03-01");
     }
 
     #[test]
-    fn datetime_right_left_up_increments_day() {
-        let (mut t, s) = datetime_init();
-        t.right().unwrap();
-        t.left().unwrap();
+    fn test_day_wraps_in_month_skipping_disabled_weekdays() {
+        // 2024-02-01 and 2024-02-29 are Thursdays
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(2024, 2, 2, 12, 0, 0).unwrap());
+        t.day_wraps_in_month = true;
+        t.disabled_weekdays = vec![Weekday::Thu];
+        t.down().unwrap();
+        assert_eq!(t.to_string(), "2024-02-28");
         t.up().unwrap();
-        assert_eq!(t.get_date(), s.get_date() + Duration::days(1))
+        assert_eq!(t.to_string(), "2024-02-02");
     }
 
     #[test]
-    fn date_right_up_increments_year() {
+    fn test_render_to_highlights_active_field() {
+        let mut t = DateSelector::from(Local.with_ymd_and_hms(12345, 6, 7, 8, 9, 10).unwrap());
+        t.name = "at".to_string();
+        t.set_has_time(true);
+        t.set_active_field(DateTimeField::Minute).unwrap();
+        let mut out = Vec::new();
+        t.render_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "at: +12345-06-07 08:\x1b[7m09\x1b[0m:10"
+        );
+    }
+
+    #[test]
+    fn test_set_has_time_false_moves_off_time_field() {
+        let (mut t, _) = datetime_init();
+        assert!(t.has_time());
+        t.set_active_field(DateTimeField::Second).unwrap();
+        t.set_has_time(false);
+        assert!(!t.has_time());
+        assert_eq!(t.active_field, DateTimeField::Day);
+    }
+
+    #[test]
+    fn test_date_toggle_twice_removes_date() {
         let (mut t, s) = date_init();
-        t.right().unwrap();
+        let mut picked = Vec::new();
+        t.toggle(&mut picked);
         t.up().unwrap();
-        assert_eq!(t.get_date(), s.get_date() + Months::new(12))
+        t.toggle(&mut picked);
+        assert_eq!(picked, vec![s.date, s.date + Duration::days(1)]);
+        t.down().unwrap();
+        t.toggle(&mut picked);
+        assert_eq!(picked, vec![s.date + Duration::days(1)]);
     }
 
     #[test]
-    fn datetime_right_up_increments_hour() {
-        let (mut t, s) = datetime_init();
-        t.right().unwrap();
-        t.up().unwrap();
-        assert_eq!(t.get_date(), s.get_date() + Duration::hours(1))
+    fn test_calendar_grid_highlights_day() {
+        let mut t = DateSelector::from(
+            Local
+                .from_local_datetime(&NaiveDate::from_ymd_opt(2024, 2, 14).unwrap().into())
+                .unwrap(),
+        );
+        t.name = "day".to_string();
+        let (grid, cursor) = t.calendar_grid();
+        assert_eq!(
+            grid,
+            concat!(
+                "day: 2024-02\n",
+                "Mo Tu We Th Fr Sa Su\n",
+                "          1  2  3  4\n",
+                " 5  6  7  8  9 10 11\n",
+                "12 13 \x1b[7m14\x1b[0m 15 16 17 18\n",
+                "19 20 21 22 23 24 25\n",
+                "26 27 28 29 ",
+            )
+        );
+        assert_eq!(cursor, (7, 4));
     }
 
     #[test]
-    fn datetime_right2_down2_decrements_minutes() {
-        let (mut t, s) = datetime_init();
-        t.right().unwrap();
-        t.right().unwrap();
-        t.down().unwrap();
-        t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date() - Duration::minutes(2))
+    fn test_calendar_grid_starts_on_week_start() {
+        let mut t = DateSelector::from(
+            Local
+                .from_local_datetime(&NaiveDate::from_ymd_opt(2024, 2, 14).unwrap().into())
+                .unwrap(),
+        );
+        t.name = "day".to_string();
+        t.week_start = Weekday::Sun;
+        let (grid, cursor) = t.calendar_grid();
+        assert!(grid.starts_with(concat!(
+            "day: 2024-02\n",
+            "Su Mo Tu We Th Fr Sa\n",
+            "             1  2  3\n",
+            " 4  5  6  7  8  9 10\n",
+        )));
+        assert_eq!(cursor, (10, 4));
     }
 
     #[test]
-    fn datetime_right3_up_increments_second() {
-        let (mut t, s) = datetime_init();
-        t.right().unwrap();
-        t.right().unwrap();
-        t.right().unwrap();
-        t.up().unwrap();
-        assert_eq!(t.get_date(), s.get_date() + Duration::seconds(1))
+    fn test_calendar_move_by_week_and_month() {
+        let (mut t, s) = date_init();
+        t.calendar = true;
+        t.handle_key(Key::ArrowDown).unwrap();
+        assert_eq!(t.date, s.date + Duration::days(7));
+        t.handle_key(Key::ArrowLeft).unwrap();
+        t.handle_key(Key::PageUp).unwrap();
+        assert_eq!(
+            t.date,
+            (s.date + Duration::days(6))
+                .checked_sub_months(Months::new(1))
+                .unwrap()
+        );
     }
 
     #[test]
-    fn datetime_right4_down_decrements_year() {
-        let (mut t, s) = datetime_init();
-        t.right().unwrap();
-        t.right().unwrap();
-        t.right().unwrap();
-        t.right().unwrap();
-        t.down().unwrap();
-        assert_eq!(t.get_date(), s.get_date() - Months::new(12))
+    fn test_date_handle_key_outputs_date_on_confirm() {
+        let (mut t, s) = date_init();
+        assert!(t.handle_key(Key::ArrowUp).unwrap().is_none());
+        assert_eq!(
+            t.handle_key(Key::Enter).unwrap(),
+            Some(s.date + Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn test_date_styled_name_keeps_cursor_column() {
+        let (mut t, s) = date_init();
+        t.name_style = Style::new().red().bold().force_styling(true);
+        assert_ne!(t.label(), s.label());
+        assert_eq!(t.cursor_column(), s.cursor_column());
+        assert_eq!(t.cursor_column(), "due date: 2000-01-0".len());
     }
 
     #[test]
@@ -580,3 +3298,529 @@ mod date_selector_tests {
         assert_ne!(t.get_date(), s.get_date())
     }
 }
+
+#[cfg(test)]
+mod word_selector_tests {
+    use crate::selector::*;
+
+    #[test]
+    fn select_word_from_no_words_results_error() {
+        let e = select_word_from_words::<&str>("nothing", &[]).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn find_ignore_case_results_byte_range_in_item() {
+        assert_eq!(find_ignore_case("Elephant", "PHA"), Some((3, 6)));
+        assert_eq!(find_ignore_case("Ünïcode Ürl", "ürl"), Some((10, 14)));
+        assert_eq!(find_ignore_case("Horse", "cat"), None);
+        assert_eq!(find_ignore_case("Horse", ""), Some((0, 0)));
+    }
+
+    #[test]
+    fn fuzzy_match_finds_scattered_characters() {
+        let (_, matched) = fuzzy_match("Elephant", "elp").unwrap();
+        assert_eq!(matched, vec![0, 1, 3]);
+        assert_eq!(fuzzy_match("Elephant", "pe"), None);
+        assert!(fuzzy_match("Help", "elp").unwrap().0 > fuzzy_match("Elephant", "elp").unwrap().0);
+        assert_eq!(
+            highlight_fuzzy("Elephant", "elp"),
+            "\x1b[1;33mEl\x1b[0me\x1b[1;33mp\x1b[0mhant"
+        );
+    }
+
+    #[test]
+    fn fuzzy_filter_sorts_by_score_keeping_list_order_for_ties() {
+        let options = ListOptions {
+            filter: true,
+            fuzzy: true,
+            initial_query: "elp".to_string(),
+            ..Default::default()
+        };
+        let items = ["Elephant", "Horse", "Help", "Yelp"];
+        let s = WordSelector::new("animal", &items, options).unwrap();
+        assert_eq!(s.visible(), vec!["Help", "Yelp", "Elephant"]);
+        assert_eq!(s.selected_index(), Some(2));
+    }
+
+    #[test]
+    fn highlight_match_styles_matched_run_only() {
+        assert_eq!(
+            highlight_match("Elephant", "pha"),
+            "Ele\x1b[1;33mpha\x1b[0mnt"
+        );
+        assert_eq!(highlight_match("Elephant", ""), "Elephant");
+        assert_eq!(
+            measure_text_width(&highlight_match("Elephant", "ele")),
+            "Elephant".len()
+        );
+    }
+
+    #[test]
+    fn fit_item_cuts_long_item_on_char_boundary() {
+        assert_eq!(fit_item("Elephant", "", 0, 8), "Elephant");
+        assert_eq!(fit_item("Elephant", "", 0, 5), "Elep…");
+        assert_eq!(fit_item("日本語の象", "", 0, 6), "日本…");
+        assert_eq!(fit_item("日本語の象", "", 2, 6), "…語の…");
+        assert_eq!(fit_item("日本語の象", "", 3, 6), "…の象");
+    }
+
+    #[test]
+    fn fit_item_keeps_highlight_closed() {
+        let line = fit_item("Elephant", "pha", 0, 5);
+        assert_eq!(measure_text_width(&line), 5);
+        assert!(line.ends_with("\x1b[0m"));
+    }
+
+    #[test]
+    fn is_cut_off_stops_scroll_at_item_tail() {
+        assert!(is_cut_off("Elephant", 0, 5));
+        assert!(is_cut_off("Elephant", 3, 5));
+        assert!(!is_cut_off("Elephant", 4, 5));
+    }
+
+    #[test]
+    fn list_options_quit_with_q_and_escape_by_default() {
+        let o = ListOptions::default();
+        assert!(o.quit_keys.contains(&Key::Char('q')));
+        assert!(o.quit_keys.contains(&Key::Escape));
+        assert!(!o.filter);
+    }
+
+    #[test]
+    fn word_selector_filters_items_by_typed_query() {
+        let items = ["Elephant", "Horse", "Whale"];
+        let options = ListOptions {
+            filter: true,
+            ..Default::default()
+        };
+        let mut s = WordSelector::new("animal", &items, options).unwrap();
+        s.handle_key(Key::Char('H')).unwrap();
+        assert_eq!(s.visible(), vec!["Elephant", "Horse", "Whale"]);
+        s.handle_key(Key::Char('a')).unwrap();
+        assert_eq!(s.visible(), vec!["Elephant", "Whale"]);
+        s.handle_key(Key::Backspace).unwrap();
+        s.handle_key(Key::ArrowDown).unwrap();
+        assert_eq!(s.seq, 1);
+    }
+
+    #[test]
+    fn word_selector_results_interrupted_on_cancel() {
+        let mut s = WordSelector::new("animal", &["Horse"], ListOptions::default()).unwrap();
+        s.set_cancel(Arc::new(AtomicBool::new(true)));
+        let e = s.select().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::Interrupted);
+    }
+
+    #[test]
+    fn word_selector_starts_with_initial_query() {
+        let items = ["Elephant", "Horse", "Whale"];
+        let options = ListOptions {
+            filter: true,
+            initial_query: "ha".to_string(),
+            ..Default::default()
+        };
+        let mut s = WordSelector::new("animal", &items, options).unwrap();
+        assert_eq!(s.visible(), vec!["Elephant", "Whale"]);
+        s.handle_key(Key::Backspace).unwrap();
+        s.handle_key(Key::Backspace).unwrap();
+        assert_eq!(s.visible(), items);
+    }
+
+    #[test]
+    fn word_selector_quits_with_quit_keys() {
+        let mut s = WordSelector::new("animal", &["Horse"], ListOptions::default()).unwrap();
+        assert!(s.handle_key(Key::Char('j')).unwrap().is_none());
+        let e = s.handle_key(Key::Char('q')).unwrap_err();
+        assert!(is_quit_error(&e));
+        assert_eq!(e.to_string(), "quit");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn word_selector_leaves_alt_screen_on_exit() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("alt-screen");
+        let options = ListOptions {
+            alt_screen: true,
+            ..Default::default()
+        };
+        let mut s = WordSelector::with_term("animal", &["Horse"], options, term).unwrap();
+        s.set_cancel(Arc::new(AtomicBool::new(true)));
+        let e = s.select().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::Interrupted);
+        drop(s);
+        let out = output(&path);
+        assert!(out.starts_with("\x1b[?1049h"));
+        assert!(out.ends_with("Horse\n\x1b[?1049l"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn word_selector_summary_replaces_only_the_frame() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("summary-frame");
+        let options = ListOptions {
+            summary: true,
+            ..Default::default()
+        };
+        let items = ["Elephant", "Horse"];
+        let mut s = WordSelector::with_term("animal", &items, options, term).unwrap();
+        assert_eq!(
+            s.handle_key(Key::Enter).unwrap(),
+            Some("Elephant".to_string())
+        );
+        drop(s);
+        let out = output(&path);
+        // the three lines of the frame are cleared upwards, not the screen
+        assert!(!out.contains("\x1b[2J"));
+        assert!(out.starts_with("\r\x1b[2K\x1b[3A"));
+        assert!(out.ends_with("\x1b[32m✓\x1b[0m animal: Elephant\n"));
+    }
+
+    #[test]
+    fn selected_index_counts_filtered_out_items() {
+        let items = ["Elephant", "Horse", "Whale"];
+        let options = ListOptions {
+            filter: true,
+            ..Default::default()
+        };
+        let mut s = WordSelector::new("animal", &items, options).unwrap();
+        s.handle_key(Key::Char('a')).unwrap();
+        s.handle_key(Key::ArrowDown).unwrap();
+        assert_eq!(s.selected_index(), Some(2));
+        s.handle_key(Key::Char('z')).unwrap();
+        assert_eq!(s.selected_index(), None);
+    }
+
+    #[test]
+    fn render_list_cuts_items_to_terminal_width() {
+        let options = ListOptions {
+            filter: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_list("animal", &["Elephant", "Horse"], 0, &options, 7),
+            "animal\n> \n\x1b[32m*\x1b[0m Elep…\n  Horse\n"
+        );
+    }
+
+    #[test]
+    fn render_list_cuts_each_description_line() {
+        let frame = render_list(
+            "long description\nof the animal",
+            &["Horse"],
+            0,
+            &ListOptions::default(),
+            10,
+        );
+        assert_eq!(frame, "long desc…\nof the an…\n\x1b[32m*\x1b[0m Horse\n");
+    }
+
+    #[test]
+    fn selection_index_goes_round() {
+        assert_eq!(prev_seq(0, 3), 2);
+        assert_eq!(prev_seq(2, 3), 1);
+        assert_eq!(next_seq(2, 3), 0);
+        assert_eq!(next_seq(0, 1), 0);
+        assert_eq!(prev_seq(0, 0), 0);
+        assert_eq!(next_seq(0, 0), 0);
+    }
+
+    #[test]
+    fn footer_is_dimmed_below_items() {
+        let options = ListOptions {
+            footer: Some(LIST_HELP.to_string()),
+            ..Default::default()
+        };
+        let frame = render_list("animal", &["Horse"], 0, &options, 80);
+        assert_eq!(
+            frame,
+            format!(
+                "animal\n\x1b[32m*\x1b[0m Horse\n\x1b[2m{}\x1b[0m\n",
+                LIST_HELP
+            )
+        );
+    }
+
+    #[test]
+    fn full_line_highlight_reverses_selected_item_only() {
+        let options = ListOptions {
+            highlight: HighlightStyle::FullLine,
+            ..Default::default()
+        };
+        let frame = render_list("animal", &["Elephant", "Horse"], 1, &options, 80);
+        assert_eq!(frame, "animal\n  Elephant\n  \x1b[7mHorse\x1b[0m\n");
+    }
+
+    #[test]
+    fn full_line_highlight_continues_after_query_match() {
+        let options = ListOptions {
+            filter: true,
+            highlight: HighlightStyle::FullLine,
+            ..Default::default()
+        };
+        let frame = render_frame("animal", &["Horse"], 0, "or", 0, &options, 80);
+        assert_eq!(
+            frame,
+            "animal\n> or\n  \x1b[7mH\x1b[1;33mor\x1b[0;7mse\x1b[0m\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn required_selection_ignores_quit_keys() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("required");
+        let options = ListOptions {
+            required: true,
+            ..Default::default()
+        };
+        let items = ["Elephant", "Horse"];
+        let mut s = WordSelector::with_term("animal", &items, options, term).unwrap();
+        assert_eq!(s.handle_key(Key::Escape).unwrap(), None);
+        assert_eq!(s.handle_key(Key::Char('q')).unwrap(), None);
+        s.handle_key(Key::ArrowDown).unwrap();
+        assert_eq!(s.selected_index(), Some(1));
+        drop(s);
+        assert_eq!(output(&path), "\x07\x07");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn required_selection_types_quit_chars_into_query() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("required-filter");
+        let options = ListOptions {
+            filter: true,
+            required: true,
+            ..Default::default()
+        };
+        let items = ["Elephant", "Quokka"];
+        let mut s = WordSelector::with_term("animal", &items, options, term).unwrap();
+        assert_eq!(s.handle_key(Key::Char('q')).unwrap(), None);
+        assert_eq!(s.query, "q");
+        assert_eq!(s.selected_index(), Some(1));
+        assert_eq!(s.handle_key(Key::Escape).unwrap(), None);
+        drop(s);
+        assert_eq!(output(&path), "\x07");
+    }
+
+    #[test]
+    fn selection_outcome_tells_index_and_value() {
+        let outcome = SelectionOutcome::Selected {
+            index: 1,
+            value: "Horse".to_string(),
+        };
+        assert_eq!(outcome.index(), Some(1));
+        assert_eq!(outcome.value(), Some("Horse"));
+        assert_eq!(SelectionOutcome::Quit.value(), None);
+    }
+
+    #[test]
+    fn multi_line_item_is_indented_under_marker() {
+        let options = ListOptions {
+            numbering: Some(1),
+            ..Default::default()
+        };
+        let frame = render_list("animal", &["Elephant\nbig ears", "Horse"], 0, &options, 80);
+        assert_eq!(
+            frame,
+            "animal\n\x1b[32m*\x1b[0m 1. Elephant\n     big ears\n  2. Horse\n"
+        );
+        assert!(is_cut_off("Ant\nsmall and strong", 0, 8));
+    }
+
+    #[test]
+    fn numbering_is_aligned_for_double_digits() {
+        let items: Vec<String> = (1..=10).map(|i| format!("item{}", i)).collect();
+        let options = ListOptions {
+            numbering: Some(1),
+            ..Default::default()
+        };
+        let frame = render_list("items", &items, 9, &options, 80);
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(lines[1], "   1. item1");
+        assert_eq!(lines[10], "\x1b[32m*\x1b[0m 10. item10");
+    }
+
+    #[test]
+    fn multi_selector_returns_checked_indices_in_list_order() {
+        let mut s = MultiSelector::new("animals", &["Elephant", "Horse", "Whale"]).unwrap();
+        s.handle_key(Key::ArrowUp).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::Char('j')).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        assert_eq!(s.checked_indices(), vec![0, 2]);
+        assert_eq!(
+            render_checkboxes("animals", &s.items, &s.checked, s.seq, 80),
+            "animals\n\x1b[32m*\x1b[0m [x] Elephant\n  [ ] Horse\n  [x] Whale\n"
+        );
+    }
+
+    #[test]
+    fn multi_selector_preserves_toggle_order() {
+        let mut s = MultiSelector::new("animals", &["Elephant", "Horse", "Whale"]).unwrap();
+        s.preserve_selection_order = true;
+        s.handle_key(Key::ArrowUp).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::Char('j')).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        assert_eq!(s.checked_indices(), vec![2, 0]);
+        s.handle_key(Key::ArrowUp).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        assert_eq!(s.checked_indices(), vec![0, 2]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn multi_selector_leaves_summary_of_checked_items() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("multi-summary");
+        let items = ["Elephant", "Horse", "Whale"];
+        let mut s = MultiSelector::with_term("animals", &items, term).unwrap();
+        s.summary = true;
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::ArrowUp).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        assert_eq!(s.handle_key(Key::Enter).unwrap(), Some(vec![0, 2]));
+        drop(s);
+        assert!(output(&path).ends_with("\x1b[32m✓\x1b[0m animals: Elephant, Whale\n"));
+    }
+
+    #[test]
+    fn multi_selector_checks_all_then_none() {
+        let mut s = MultiSelector::new("animals", &["Elephant", "Horse", "Whale"]).unwrap();
+        s.handle_key(Key::Char(' ')).unwrap();
+        s.handle_key(Key::Char('a')).unwrap();
+        assert_eq!(s.checked_indices(), vec![0, 1, 2]);
+        s.handle_key(Key::Char('A')).unwrap();
+        assert!(s.checked_indices().is_empty());
+    }
+
+    #[test]
+    fn list_selector_builder_sets_options() {
+        let s = ListSelector::new("animal")
+            .filter(true)
+            .initial_query("ha")
+            .numbering(0)
+            .footer(LIST_HELP);
+        assert!(s.options.filter);
+        assert_eq!(s.options.initial_query, "ha");
+        assert_eq!(s.options.numbering, Some(0));
+        assert_eq!(s.options.footer.as_deref(), Some(LIST_HELP));
+        assert_eq!(s.options.quit_keys, vec![Key::Escape]);
+        let s = ListSelector::new("animal").filter(false);
+        assert_eq!(s.options.quit_keys, ListOptions::default().quit_keys);
+        let s = ListSelector::new("animal")
+            .quit_keys(vec![Key::Char('q')])
+            .filter(true);
+        assert_eq!(s.options.quit_keys, vec![Key::Char('q')]);
+    }
+
+    #[test]
+    fn select_with_renderer_rejects_zero_items() {
+        let e = select_with_renderer("files", 0, |i, _| i.to_string()).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn summary_line_has_symbol_if_any() {
+        assert_eq!(summary_line("", "animal", "Horse"), "animal: Horse");
+        assert_eq!(
+            summary_line(SUMMARY_SYMBOL, "animal", "Horse"),
+            "\x1b[32m✓\x1b[0m animal: Horse"
+        );
+    }
+
+    #[test]
+    fn mark_table_for_a_word_marks_it() {
+        assert_eq!(mark_table(1, 0), vec!["\x1b[32m*\x1b[0m"]);
+    }
+
+    #[test]
+    fn mark_table_marks_selected_word_only() {
+        let t = mark_table(3, 1);
+        assert_eq!(t, vec![" ", "\x1b[32m*\x1b[0m", " "]);
+    }
+}
+
+#[cfg(test)]
+mod yes_no_tests {
+    use crate::selector::*;
+
+    #[test]
+    fn default_options_keep_colon_and_single_char_echo() {
+        let o = YesNoOptions::default();
+        assert_eq!(o.suffix, ": ");
+        assert_eq!((o.yes_echo.as_str(), o.no_echo.as_str()), ("y", "n"));
+        assert!(o.newline);
+        assert!(!o.clear_line);
+        assert_eq!(o.reprompt, DEFAULT_YES_NO_REPROMPT);
+    }
+
+    fn yes_no(key: &Key) -> Option<(bool, &'static str)> {
+        match key {
+            Key::Char('y') => Some((true, "yes")),
+            Key::Char('n') => Some((false, "no")),
+            _ => None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn answer_is_echoed_after_suffix_without_newline() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("yes-no-echo");
+        let options = YesNoOptions {
+            suffix: " ? ".to_string(),
+            newline: false,
+            ..Default::default()
+        };
+        let mut keys = vec![Key::Char('n')].into_iter();
+        let value = ask_answer_on(
+            &term,
+            "Delete",
+            &options,
+            || Ok(keys.next().unwrap()),
+            yes_no,
+        );
+        assert!(!value.unwrap());
+        assert_eq!(output(&path), "Delete ? no");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn other_keys_reprompt_with_suffix() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("yes-no-reprompt");
+        let options = YesNoOptions {
+            reprompt: "y or n".to_string(),
+            ..Default::default()
+        };
+        let mut keys = vec![Key::Char('x'), Key::Char('y')].into_iter();
+        let value = ask_answer_on(&term, "Go", &options, || Ok(keys.next().unwrap()), yes_no);
+        assert!(value.unwrap());
+        let out = output(&path);
+        assert!(out.starts_with("Go: "));
+        assert!(out.ends_with("y or n: yes\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clear_line_erases_the_question_instead_of_echo() {
+        use crate::terminal::tests::{file_term, output};
+        let (term, path) = file_term("yes-no-clear");
+        let options = YesNoOptions {
+            clear_line: true,
+            ..Default::default()
+        };
+        let mut keys = vec![Key::Char('y')].into_iter();
+        let value = ask_answer_on(&term, "Go", &options, || Ok(keys.next().unwrap()), yes_no);
+        assert!(value.unwrap());
+        assert_eq!(output(&path), "Go: \r\x1b[2K");
+    }
+}
@@ -0,0 +1,209 @@
+//! Terminal setup around the prompts, which is restored however they end.
+//!
+//! A prompt which returns an error or panics in the middle can leave the cursor hidden,
+//! the tty in the raw mode or the alternate screen shown. with_terminal() restores them
+//! on any exit, including the unwinding of a panic.
+//!
+
+use std::io;
+
+#[cfg(unix)]
+use crate::poll::RawTty;
+use console::Term;
+
+/// Setup of the terminal for with_terminal_options().
+///
+/// The default leaves the tty mode, the cursor and the screen as they are.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TerminalOptions {
+    /// hide the cursor, which is shown again on exit
+    pub hide_cursor: bool,
+    /// switch the tty to the raw mode, keeping the output processing, not to echo keys typed
+    /// between the prompts; Ctrl-C is then read as a key rather than raising SIGINT
+    pub raw_mode: bool,
+    /// run in the alternate screen, which restores the previous screen on exit
+    pub alt_screen: bool,
+}
+
+/// Run `f` with the terminal set up by the default options, and restore it afterwards.
+///
+/// ```rust,no_run
+/// use ttyui::selector::ask_yes_no;
+/// use ttyui::terminal::with_terminal;
+///
+/// let answer = with_terminal(|| ask_yes_no("Continue?")).unwrap();
+/// ```
+///
+pub fn with_terminal<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    with_terminal_options(&TerminalOptions::default(), f)
+}
+
+/// Run `f` with the terminal set up by the options, and restore it afterwards
+/// even if `f` results an error or panics.
+///
+/// Nothing is set up when the standard output is not a terminal.
+/// The error of `f` is returned rather than the one of the restore, if both fail.
+///
+/// ```rust,no_run
+/// use ttyui::readline::read_line;
+/// use ttyui::terminal::{with_terminal_options, TerminalOptions};
+///
+/// let options = TerminalOptions {
+///     alt_screen: true,
+///     ..Default::default()
+/// };
+/// let name = with_terminal_options(&options, read_line).unwrap();
+/// println!("{}", name);
+/// ```
+///
+pub fn with_terminal_options<T>(
+    options: &TerminalOptions,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        return f();
+    }
+    restored(term, options, f)
+}
+
+/// Run `f` with the terminal set up by the options, and restore it afterwards.
+///
+fn restored<T>(
+    term: Term,
+    options: &TerminalOptions,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let guard = Restore::enter(term, options)?;
+    let res = f();
+    let restore = guard.restore();
+    let value = res?;
+    restore?;
+    Ok(value)
+}
+
+/// Terminal set up by with_terminal_options(), which is restored on restore() or drop.
+///
+/// Each step of the setup is recorded as it is done, so that only those are undone.
+///
+struct Restore {
+    term: Term,
+    hide_cursor: bool,
+    alt_screen: bool,
+    #[cfg(unix)]
+    raw: Option<RawTty>,
+}
+
+impl Restore {
+    fn enter(term: Term, options: &TerminalOptions) -> io::Result<Self> {
+        let mut guard = Restore {
+            term,
+            hide_cursor: false,
+            alt_screen: false,
+            #[cfg(unix)]
+            raw: None,
+        };
+        if options.alt_screen {
+            guard.term.write_str("\x1b[?1049h")?;
+            guard.alt_screen = true;
+        }
+        if options.hide_cursor {
+            guard.term.hide_cursor()?;
+            guard.hide_cursor = true;
+        }
+        #[cfg(unix)]
+        if options.raw_mode {
+            guard.raw = Some(RawTty::enter()?);
+        }
+        Ok(guard)
+    }
+
+    /// Restore the terminal, with the error which drop cannot report.
+    ///
+    fn restore(mut self) -> io::Result<()> {
+        self.reset()
+    }
+
+    /// Undo the steps of the setup in the reverse order, all of which are tried even if one fails.
+    ///
+    fn reset(&mut self) -> io::Result<()> {
+        #[cfg(unix)]
+        let raw = match self.raw.take() {
+            Some(raw) => raw.restore(),
+            None => Ok(()),
+        };
+        #[cfg(not(unix))]
+        let raw = Ok(());
+        let cursor = match std::mem::take(&mut self.hide_cursor) {
+            true => self.term.show_cursor(),
+            false => Ok(()),
+        };
+        let screen = match std::mem::take(&mut self.alt_screen) {
+            true => self.term.write_str("\x1b[?1049l"),
+            false => Ok(()),
+        };
+        raw.and(cursor).and(screen)
+    }
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let _ = self.reset();
+    }
+}
+
+#[cfg(all(test, unix))]
+pub(crate) mod tests {
+    use crate::terminal::*;
+    use std::fs::File;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    const OPTIONS: TerminalOptions = TerminalOptions {
+        hide_cursor: true,
+        raw_mode: false,
+        alt_screen: true,
+    };
+
+    /// Terminal writing to a file, which is not a tty, and the path of the file.
+    /// The name tells the file from the ones of the other tests.
+    pub(crate) fn file_term(name: &str) -> (Term, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("ttyui-{}-{}", name, std::process::id()));
+        let file = File::create(&path).unwrap();
+        let term = Term::read_write_pair(File::open(&path).unwrap(), file);
+        assert!(!term.is_term());
+        (term, path)
+    }
+
+    /// Output written to the file, which is removed.
+    pub(crate) fn output(path: &std::path::Path) -> String {
+        let s = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        s
+    }
+
+    #[test]
+    fn test_restores_on_ok() {
+        let (term, path) = file_term("ok");
+        assert_eq!(restored(term, &OPTIONS, || Ok(1)).unwrap(), 1);
+        assert_eq!(output(&path), "\x1b[?1049h\x1b[?25l\x1b[?25h\x1b[?1049l");
+    }
+
+    #[test]
+    fn test_restores_on_error() {
+        let (term, path) = file_term("err");
+        let res: io::Result<()> = restored(term, &OPTIONS, || Err(io::Error::other("failed")));
+        assert_eq!(res.unwrap_err().to_string(), "failed");
+        assert!(output(&path).ends_with("\x1b[?25h\x1b[?1049l"));
+    }
+
+    #[test]
+    fn test_restores_on_panic() {
+        let (term, path) = file_term("panic");
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            restored::<()>(term, &OPTIONS, || panic!("in the prompt"))
+        }));
+        assert!(res.is_err());
+        assert!(output(&path).ends_with("\x1b[?25h\x1b[?1049l"));
+    }
+}